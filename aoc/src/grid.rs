@@ -0,0 +1,298 @@
+//! A rectangular grid of cells, like most puzzle inputs.
+
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::{Vec2, VecN};
+
+/// Steps to the cells sharing an edge, clockwise from the right.
+pub const NEIGHBOURS_4: [Vec2<isize>; 4] =
+    [VecN([1, 0]), VecN([0, 1]), VecN([-1, 0]), VecN([0, -1])];
+
+/// Steps to the cells sharing an edge or a corner, clockwise from the right.
+pub const NEIGHBOURS_8: [Vec2<isize>; 8] = [
+    VecN([1, 0]),
+    VecN([1, 1]),
+    VecN([0, 1]),
+    VecN([-1, 1]),
+    VecN([-1, 0]),
+    VecN([-1, -1]),
+    VecN([0, -1]),
+    VecN([1, -1]),
+];
+
+/// Cells stored row by row, addressed by `(x, y)` with `(0, 0)` at the top
+/// left. Positions are signed so that stepping off the edge is just a failed
+/// lookup.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    size: Vec2<usize>,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(size: Vec2<usize>, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            size,
+            cells: vec![fill; size.0.iter().product()],
+        }
+    }
+
+    /// Parse one row per line of `text`, turning each character into a cell
+    /// with `cell`, which returns `None` for characters that don't belong.
+    /// Every row must be the same length.
+    pub fn parse(text: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, String> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for (y, line) in text.lines().enumerate() {
+            let length = line.chars().count();
+            match width {
+                None => width = Some(length),
+                Some(width) if width != length => {
+                    return Err(format!(
+                        "line {} has {length} characters, but line 1 has {width}",
+                        y + 1
+                    ));
+                }
+                Some(_) => {}
+            }
+
+            for (x, c) in line.chars().enumerate() {
+                let value = cell(c).ok_or_else(|| {
+                    format!("unexpected {c:?} at line {}, column {}", y + 1, x + 1)
+                })?;
+                cells.push(value);
+            }
+
+            height += 1;
+        }
+
+        Ok(Self {
+            size: Vec2::new(width.unwrap_or(0), height),
+            cells,
+        })
+    }
+
+    pub fn size(&self) -> Vec2<usize> {
+        self.size
+    }
+
+    pub fn width(&self) -> usize {
+        self.size.x()
+    }
+
+    pub fn height(&self) -> usize {
+        self.size.y()
+    }
+
+    pub fn contains(&self, position: Vec2<isize>) -> bool {
+        position.is_inside(&self.size)
+    }
+
+    pub fn get(&self, position: Vec2<isize>) -> Option<&T> {
+        self.contains(position)
+            .then(|| &self.cells[position.index(&self.size)])
+    }
+
+    pub fn get_mut(&mut self, position: Vec2<isize>) -> Option<&mut T> {
+        self.contains(position)
+            .then(|| &mut self.cells[position.index(&self.size)])
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Vec2<isize>> + '_ {
+        VecN::points(&self.size)
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2<isize>, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The positions of every cell equal to `glyph`, row by row.
+    pub fn find<'a>(&'a self, glyph: &'a T) -> impl Iterator<Item = Vec2<isize>> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == glyph)
+            .map(|(position, _)| position)
+    }
+
+    /// The positions sharing an edge with `position` that are on the grid.
+    pub fn neighbours_4(&self, position: Vec2<isize>) -> impl Iterator<Item = Vec2<isize>> + '_ {
+        self.neighbours(position, &NEIGHBOURS_4)
+    }
+
+    /// The positions sharing an edge or a corner with `position` that are on
+    /// the grid.
+    pub fn neighbours_8(&self, position: Vec2<isize>) -> impl Iterator<Item = Vec2<isize>> + '_ {
+        self.neighbours(position, &NEIGHBOURS_8)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        position: Vec2<isize>,
+        steps: &'a [Vec2<isize>],
+    ) -> impl Iterator<Item = Vec2<isize>> + 'a {
+        steps
+            .iter()
+            .map(move |step| position + *step)
+            .filter(|neighbour| self.contains(*neighbour))
+    }
+
+    /// The cells from `start` onwards, moving by `step` each time, until the
+    /// edge of the grid.
+    pub fn line(
+        &self,
+        start: Vec2<isize>,
+        step: Vec2<isize>,
+    ) -> impl Iterator<Item = &T> + Clone + '_ {
+        (0..).map_while(move |i| self.get(start + step * i))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` can't take a zero size, but then there are no cells anyway
+        self.cells.chunks(self.width().max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + Clone + '_> {
+        (0..self.width() as isize).map(|x| self.line(Vec2::new(x, 0), Vec2::new(0, 1)))
+    }
+
+    /// Every line running down and to the right, starting from the bottom
+    /// left.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + Clone + '_> {
+        let (width, height) = (self.width() as isize, self.height() as isize);
+        let starts = (1..height)
+            .rev()
+            .map(|y| Vec2::new(0, y))
+            .chain((0..width).map(|x| Vec2::new(x, 0)));
+
+        starts.map(|start| self.line(start, Vec2::new(1, 1)))
+    }
+
+    /// Every line running down and to the left, starting from the top left.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + Clone + '_> {
+        let (width, height) = (self.width() as isize, self.height() as isize);
+        let starts = (0..width)
+            .map(|x| Vec2::new(x, 0))
+            .chain((1..height).map(move |y| Vec2::new(width - 1, y)));
+
+        starts.map(|start| self.line(start, Vec2::new(-1, 1)))
+    }
+
+    /// A grid of the same size with every cell converted by `f`.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            size: self.size,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Vec2<isize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Vec2<isize>) -> &Self::Output {
+        self.get(position)
+            .unwrap_or_else(|| panic!("{position:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Vec2<isize>> for Grid<T> {
+    fn index_mut(&mut self, position: Vec2<isize>) -> &mut Self::Output {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{position:?} is outside the grid"))
+    }
+}
+
+/// One line per row, with each cell written in turn.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(text: &str) -> Grid<char> {
+        Grid::parse(text, Some).expect("a rectangular grid")
+    }
+
+    fn strings<'a, L>(lines: impl Iterator<Item = L>) -> Vec<String>
+    where
+        L: Iterator<Item = &'a char>,
+    {
+        lines.map(|line| line.collect()).collect()
+    }
+
+    #[test]
+    fn parse_rejects_ragged_rows() {
+        assert_eq!(
+            Grid::parse("abc\nde\n", Some),
+            Err("line 2 has 2 characters, but line 1 has 3".to_string())
+        );
+    }
+
+    #[test]
+    fn lines_of_a_wide_grid() {
+        let grid = chars("abc\ndef\n");
+
+        assert_eq!(grid.size(), Vec2::new(3, 2));
+        assert_eq!(
+            grid.rows().map(String::from_iter).collect::<Vec<_>>(),
+            ["abc", "def"]
+        );
+        assert_eq!(strings(grid.columns()), ["ad", "be", "cf"]);
+        assert_eq!(strings(grid.diagonals()), ["d", "ae", "bf", "c"]);
+        assert_eq!(strings(grid.anti_diagonals()), ["a", "bd", "ce", "f"]);
+    }
+
+    #[test]
+    fn lines_of_a_tall_grid() {
+        let grid = chars("ab\ncd\nef\n");
+
+        assert_eq!(
+            grid.rows().map(String::from_iter).collect::<Vec<_>>(),
+            ["ab", "cd", "ef"]
+        );
+        assert_eq!(strings(grid.columns()), ["ace", "bdf"]);
+        assert_eq!(strings(grid.diagonals()), ["e", "cf", "ad", "b"]);
+        assert_eq!(strings(grid.anti_diagonals()), ["a", "bc", "de", "f"]);
+    }
+
+    #[test]
+    fn line_stops_at_the_edge() {
+        let grid = chars("abc\ndef\n");
+
+        let line = |start, step| grid.line(start, step).collect::<String>();
+        assert_eq!(line(Vec2::new(0, 0), Vec2::new(1, 0)), "abc");
+        assert_eq!(line(Vec2::new(1, 0), Vec2::new(1, 1)), "bf");
+        assert_eq!(line(Vec2::new(2, 1), Vec2::new(-1, -1)), "fb");
+        assert_eq!(line(Vec2::new(0, 1), Vec2::new(0, -1)), "da");
+        assert_eq!(line(Vec2::new(3, 0), Vec2::new(-1, 0)), "");
+    }
+
+    #[test]
+    fn display_round_trips_parse() {
+        let text = "#.#.\n..##\n#...\n";
+        assert_eq!(chars(text).to_string(), text);
+    }
+}
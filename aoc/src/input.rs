@@ -0,0 +1,278 @@
+//! Downloading puzzle inputs, once each.
+//!
+//! Inputs are cached under `inputs/<year>/<day>.txt` and never downloaded
+//! again once there. Downloads are at least [`Inputs::interval`] apart, even
+//! across runs, to go easy on the server.
+//!
+//! Downloading needs `curl` on the `PATH`, which does the HTTPS. Cached
+//! inputs can be read without it.
+
+use std::{
+    error::Error,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub struct Inputs {
+    /// Where inputs are cached.
+    pub cache: PathBuf,
+    /// The site to download from, without a trailing slash.
+    pub base_url: String,
+    pub year: u16,
+    /// The value of the site's `session` cookie for a logged in user.
+    pub session: Option<String>,
+    /// The least time between downloads.
+    pub interval: Duration,
+}
+
+impl Inputs {
+    /// Settings from the environment: `AOC_SESSION` (or the contents of
+    /// `.session`), `AOC_BASE_URL` and `AOC_YEAR`, with everything cached in
+    /// `inputs/`.
+    pub fn from_env() -> Result<Self, Box<dyn Error>> {
+        let session = match std::env::var("AOC_SESSION") {
+            Ok(session) => Some(session),
+            Err(_) => std::fs::read_to_string(".session").ok(),
+        };
+
+        Ok(Self {
+            cache: PathBuf::from("inputs"),
+            base_url: std::env::var("AOC_BASE_URL")
+                .unwrap_or_else(|_| "https://adventofcode.com".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            year: match std::env::var("AOC_YEAR") {
+                Ok(year) => year.parse()?,
+                Err(_) => 2024,
+            },
+            session: session.map(|session| session.trim().to_string()),
+            interval: Duration::from_secs(5),
+        })
+    }
+
+    /// Where the input for `day` is cached.
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.cache
+            .join(self.year.to_string())
+            .join(format!("{day:02}.txt"))
+    }
+
+    /// The input for `day`, from the cache if it's there and downloaded into
+    /// it otherwise.
+    pub fn get(&self, day: u8) -> Result<String, Box<dyn Error>> {
+        if !(1..=25).contains(&day) {
+            return Err(format!("there is no day {day}").into());
+        }
+
+        let path = self.path(day);
+        if path.exists() {
+            return Ok(std::fs::read_to_string(path)?);
+        }
+
+        let input = self.download(day)?;
+
+        // write then rename, so an interrupted run can't leave half an input
+        // in the cache
+        std::fs::create_dir_all(path.parent().ok_or("the cache has no directory")?)?;
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, &input)?;
+        std::fs::rename(partial, path)?;
+
+        Ok(input)
+    }
+
+    fn download(&self, day: u8) -> Result<String, Box<dyn Error>> {
+        let session = self
+            .session
+            .as_deref()
+            .ok_or("day not cached, and no session token in AOC_SESSION or .session")?;
+
+        self.wait_for_turn()?;
+
+        // the cookie is passed on stdin rather than as an argument, which
+        // other users could see
+        let url = format!("{}/{}/day/{day}/input", self.base_url, self.year);
+        let mut curl = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--header", "@-"])
+            .arg(&url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => {
+                    "downloading inputs needs curl, which isn't on the PATH".to_string()
+                }
+                _ => format!("couldn't run curl: {e}"),
+            })?;
+
+        curl.stdin
+            .take()
+            .ok_or("curl has no stdin")?
+            .write_all(format!("Cookie: session={session}\n").as_bytes())?;
+
+        let output = curl.wait_with_output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "downloading {url} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    /// Sleep until `interval` has passed since the last download, and record
+    /// this one.
+    fn wait_for_turn(&self) -> Result<(), Box<dyn Error>> {
+        let stamp = self.cache.join(".last-download");
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+        let last = std::fs::read_to_string(&stamp)
+            .ok()
+            .and_then(|last| last.trim().parse().ok())
+            .map(Duration::from_millis);
+
+        if let Some(wait) = last.and_then(|last| (last + self.interval).checked_sub(now)) {
+            std::thread::sleep(wait);
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        std::fs::create_dir_all(&self.cache)?;
+        std::fs::write(stamp, now.as_millis().to_string())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+        sync::mpsc::{self, Receiver},
+        thread,
+        time::Instant,
+    };
+
+    /// A request received by [`serve`].
+    struct Request {
+        line: String,
+        cookie: Option<String>,
+        at: Instant,
+    }
+
+    /// Stand in for the site on a local port, answering every request with
+    /// an input naming its path. Returns the base URL and each request
+    /// received.
+    fn serve() -> (String, Receiver<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("a free port");
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, requests) = mpsc::channel();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let at = Instant::now();
+
+                let mut cookie = None;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    let header = header.trim_end();
+                    if header.is_empty() {
+                        break;
+                    }
+                    if let Some(value) = header.strip_prefix("Cookie: ") {
+                        cookie = Some(value.to_string());
+                    }
+                }
+
+                let path = line.split(' ').nth(1).unwrap_or_default().to_string();
+                let body = format!("input for {path}\n");
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+
+                let line = line.trim_end().to_string();
+                if sender.send(Request { line, cookie, at }).is_err() {
+                    break;
+                }
+            }
+        });
+
+        (base_url, requests)
+    }
+
+    /// Settings for downloading from `base_url` into a fresh cache.
+    fn inputs(name: &str, base_url: String) -> Inputs {
+        let cache = std::env::temp_dir().join(format!("aoc-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache);
+
+        Inputs {
+            cache,
+            base_url,
+            year: 2024,
+            session: Some("token".to_string()),
+            interval: Duration::from_millis(300),
+        }
+    }
+
+    #[test]
+    fn downloads_into_the_cache() {
+        let (base_url, requests) = serve();
+        let inputs = inputs("download", base_url);
+
+        assert_eq!(inputs.get(3).unwrap(), "input for /2024/day/3/input\n");
+        assert_eq!(
+            std::fs::read_to_string(inputs.cache.join("2024").join("03.txt")).unwrap(),
+            "input for /2024/day/3/input\n"
+        );
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line, "GET /2024/day/3/input HTTP/1.1");
+        assert_eq!(request.cookie.as_deref(), Some("session=token"));
+
+        std::fs::remove_dir_all(&inputs.cache).unwrap();
+    }
+
+    #[test]
+    fn cached_inputs_are_not_downloaded_again() {
+        let (base_url, requests) = serve();
+        let inputs = inputs("cached", base_url);
+
+        let first = inputs.get(5).unwrap();
+        assert_eq!(inputs.get(5).unwrap(), first);
+
+        requests.recv().unwrap();
+        assert!(requests.recv_timeout(Duration::from_millis(100)).is_err());
+
+        std::fs::remove_dir_all(&inputs.cache).unwrap();
+    }
+
+    #[test]
+    fn downloads_are_spaced_out() {
+        let (base_url, requests) = serve();
+        let inputs = inputs("interval", base_url);
+
+        let start = Instant::now();
+        inputs.get(1).unwrap();
+        inputs.get(2).unwrap();
+
+        requests.recv().unwrap();
+        let second = requests.recv().unwrap();
+        assert!(second.at.duration_since(start) >= inputs.interval);
+
+        std::fs::remove_dir_all(&inputs.cache).unwrap();
+    }
+}
@@ -0,0 +1,10 @@
+//! Building blocks shared between days.
+
+mod grid;
+pub mod input;
+mod search;
+mod vec;
+
+pub use grid::{Grid, NEIGHBOURS_4, NEIGHBOURS_8};
+pub use search::{a_star, bfs, dijkstra, Graph, Maze, Path};
+pub use vec::{Vec2, VecN};
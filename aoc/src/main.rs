@@ -0,0 +1,60 @@
+use aoc::input::Inputs;
+
+/// Print a day's input, downloading it first if it isn't cached, e.g.
+/// `cargo run -p aoc -- 8 | cargo run -p day8`.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let options = Options::from_args()?;
+
+    let mut inputs = Inputs::from_env()?;
+    if let Some(year) = options.year {
+        inputs.year = year;
+    }
+    if let Some(base_url) = options.base_url {
+        inputs.base_url = base_url.trim_end_matches('/').to_string();
+    }
+
+    print!("{}", inputs.get(options.day)?);
+
+    Ok(())
+}
+
+struct Options {
+    day: u8,
+    year: Option<u16>,
+    base_url: Option<String>,
+}
+
+impl Options {
+    fn from_args() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut day = None;
+        let mut year = None;
+        let mut base_url = None;
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--year" => {
+                    let value = args.next().ok_or("--year expects a year")?;
+                    year = Some(value.parse()?);
+                }
+                "--base-url" => {
+                    base_url = Some(args.next().ok_or("--base-url expects a URL")?);
+                }
+                other if day.is_none() => {
+                    day = Some(
+                        other
+                            .parse()
+                            .map_err(|_| format!("unrecognized argument: {other}"))?,
+                    );
+                }
+                other => return Err(format!("unrecognized argument: {other}").into()),
+            }
+        }
+
+        Ok(Self {
+            day: day.ok_or("expected a day")?,
+            year,
+            base_url,
+        })
+    }
+}
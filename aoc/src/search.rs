@@ -0,0 +1,300 @@
+//! Shortest paths through anything that can list the neighbours of a node.
+
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+use crate::{Grid, Vec2};
+
+/// Something to search, described by the nodes one step away from each node.
+///
+/// Closures from a node to its neighbours implement this, so a graph can be
+/// written in place.
+pub trait Graph<N> {
+    /// The nodes reachable from `node` in one step, with the cost of each
+    /// step.
+    fn neighbours(&self, node: &N) -> impl IntoIterator<Item = (N, u64)>;
+}
+
+impl<N, F, I> Graph<N> for F
+where
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    fn neighbours(&self, node: &N) -> impl IntoIterator<Item = (N, u64)> {
+        self(node)
+    }
+}
+
+/// The open cells of a grid, with a step of cost 1 between cells sharing an
+/// edge.
+pub struct Maze<'a, T, F> {
+    grid: &'a Grid<T>,
+    open: F,
+}
+
+impl<T> Grid<T> {
+    /// A graph of the cells for which `open` is true.
+    pub fn maze<F: Fn(&T) -> bool>(&self, open: F) -> Maze<'_, T, F> {
+        Maze { grid: self, open }
+    }
+}
+
+impl<T, F: Fn(&T) -> bool> Graph<Vec2<isize>> for Maze<'_, T, F> {
+    fn neighbours(&self, node: &Vec2<isize>) -> impl IntoIterator<Item = (Vec2<isize>, u64)> {
+        self.grid
+            .neighbours_4(*node)
+            .filter(|neighbour| (self.open)(&self.grid[*neighbour]))
+            .map(|neighbour| (neighbour, 1))
+    }
+}
+
+/// A route from the start of a search to a goal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<N> {
+    /// Every node along the way, including the start and the goal.
+    pub nodes: Vec<N>,
+    /// The total cost of the steps, or their number for a breadth-first
+    /// search.
+    pub cost: u64,
+}
+
+/// A node reached during a search, and how.
+struct Visit<N> {
+    node: N,
+    cost: u64,
+    previous: Option<usize>,
+    done: bool,
+}
+
+/// Follow `previous` links back from the visit at `id` to the start.
+fn path<N: Clone>(visits: &[Visit<N>], id: usize) -> Path<N> {
+    let mut nodes = Vec::new();
+    let mut current = Some(id);
+
+    while let Some(id) = current {
+        nodes.push(visits[id].node.clone());
+        current = visits[id].previous;
+    }
+    nodes.reverse();
+
+    Path {
+        nodes,
+        cost: visits[id].cost,
+    }
+}
+
+/// The path from `start` to the nearest node satisfying `is_goal` in the
+/// fewest steps, ignoring step costs.
+pub fn bfs<N, G>(graph: &G, start: N, mut is_goal: impl FnMut(&N) -> bool) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    G: Graph<N>,
+{
+    let mut ids = HashMap::from([(start.clone(), 0)]);
+    let mut visits = vec![Visit {
+        node: start,
+        cost: 0,
+        previous: None,
+        done: true,
+    }];
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(id) = queue.pop_front() {
+        if is_goal(&visits[id].node) {
+            return Some(path(&visits, id));
+        }
+
+        let node = visits[id].node.clone();
+        for (next, _) in graph.neighbours(&node) {
+            if let Entry::Vacant(entry) = ids.entry(next) {
+                let next_id = visits.len();
+                visits.push(Visit {
+                    node: entry.key().clone(),
+                    cost: visits[id].cost + 1,
+                    previous: Some(id),
+                    done: true,
+                });
+                entry.insert(next_id);
+                queue.push_back(next_id);
+            }
+        }
+    }
+
+    None
+}
+
+/// The cheapest path from `start` to a node satisfying `is_goal`.
+pub fn dijkstra<N, G>(graph: &G, start: N, is_goal: impl FnMut(&N) -> bool) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    G: Graph<N>,
+{
+    a_star(graph, start, is_goal, |_| 0)
+}
+
+/// The cheapest path from `start` to a node satisfying `is_goal`, exploring
+/// nodes that `heuristic` estimates are closer to a goal first.
+///
+/// The heuristic must never overestimate the remaining cost, nor drop by more
+/// than the cost of a step between neighbours (e.g. manhattan distance on a
+/// grid with steps costing at least 1), or the path found might not be the
+/// cheapest.
+pub fn a_star<N, G>(
+    graph: &G,
+    start: N,
+    mut is_goal: impl FnMut(&N) -> bool,
+    mut heuristic: impl FnMut(&N) -> u64,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    G: Graph<N>,
+{
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0))]);
+    let mut ids = HashMap::from([(start.clone(), 0)]);
+    let mut visits = vec![Visit {
+        node: start,
+        cost: 0,
+        previous: None,
+        done: false,
+    }];
+
+    while let Some(Reverse((_, id))) = heap.pop() {
+        // a node can be queued again after a cheaper way to it is found
+        if visits[id].done {
+            continue;
+        }
+        visits[id].done = true;
+
+        if is_goal(&visits[id].node) {
+            return Some(path(&visits, id));
+        }
+
+        let node = visits[id].node.clone();
+        for (next, step) in graph.neighbours(&node) {
+            let cost = visits[id].cost + step;
+
+            match ids.entry(next) {
+                Entry::Vacant(entry) => {
+                    let next_id = visits.len();
+                    heap.push(Reverse((cost + heuristic(entry.key()), next_id)));
+                    visits.push(Visit {
+                        node: entry.key().clone(),
+                        cost,
+                        previous: Some(id),
+                        done: false,
+                    });
+                    entry.insert(next_id);
+                }
+                Entry::Occupied(entry) => {
+                    let next_id = *entry.get();
+                    let visit = &mut visits[next_id];
+                    if !visit.done && cost < visit.cost {
+                        visit.cost = cost;
+                        visit.previous = Some(id);
+                        heap.push(Reverse((cost + heuristic(entry.key()), next_id)));
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A maze of `#` walls from `S` to `G`.
+    fn maze(text: &str) -> (Grid<char>, Vec2<isize>, Vec2<isize>) {
+        let grid = Grid::parse(text, Some).expect("a rectangular maze");
+        let start = grid.find(&'S').next().expect("a start");
+        let goal = grid.find(&'G').next().expect("a goal");
+        (grid, start, goal)
+    }
+
+    #[test]
+    fn shortest_path_through_a_maze() {
+        let (grid, start, goal) = maze("S#...\n.#.#.\n...#G\n");
+        let path = bfs(&grid.maze(|c| *c != '#'), start, |node| *node == goal).expect("a path");
+
+        let nodes = [
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+            (2, 1),
+            (2, 0),
+            (3, 0),
+            (4, 0),
+            (4, 1),
+            (4, 2),
+        ];
+        assert_eq!(path.cost, 10);
+        assert_eq!(path.nodes, nodes.map(|(x, y)| Vec2::new(x, y)));
+        assert_eq!(
+            dijkstra(&grid.maze(|c| *c != '#'), start, |node| *node == goal),
+            Some(path)
+        );
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let (grid, start, goal) = maze("S#G\n.#.\n");
+        let graph = grid.maze(|c| *c != '#');
+
+        assert_eq!(bfs(&graph, start, |node| *node == goal), None);
+        assert_eq!(dijkstra(&graph, start, |node| *node == goal), None);
+        assert_eq!(a_star(&graph, start, |node| *node == goal, |_| 0), None);
+    }
+
+    #[test]
+    fn weighted_edges() {
+        // one expensive step straight to the goal, or three cheap ones
+        let graph = |node: &char| match node {
+            'a' => vec![('d', 10), ('b', 1)],
+            'b' => vec![('c', 1)],
+            'c' => vec![('d', 1)],
+            _ => vec![],
+        };
+
+        let fewest_steps = bfs(&graph, 'a', |node| *node == 'd').expect("a path");
+        assert_eq!(fewest_steps.nodes, ['a', 'd']);
+        assert_eq!(fewest_steps.cost, 1);
+
+        let cheapest = dijkstra(&graph, 'a', |node| *node == 'd').expect("a path");
+        assert_eq!(cheapest.nodes, ['a', 'b', 'c', 'd']);
+        assert_eq!(cheapest.cost, 3);
+    }
+
+    #[test]
+    fn a_star_agrees_with_dijkstra() {
+        let (grid, start, goal) = maze(concat!(
+            ".........\n",
+            ".#######.\n",
+            ".#.....#.\n",
+            ".#.###.#.\n",
+            "S..#G#...\n",
+            "####.###.\n",
+            ".........\n",
+        ));
+        let graph = grid.maze(|c| *c != '#');
+
+        let cheapest = dijkstra(&graph, start, |node| *node == goal).expect("a path");
+        let found = a_star(
+            &graph,
+            start,
+            |node| *node == goal,
+            |node| node.manhattan_distance(&goal) as u64,
+        )
+        .expect("a path");
+
+        assert_eq!(found.cost, cheapest.cost);
+        assert_eq!(found.nodes.len() as u64, found.cost + 1);
+        assert_eq!(found.nodes.last(), Some(&goal));
+    }
+}
@@ -0,0 +1,101 @@
+//! Points and displacements on a grid of any number of dimensions.
+
+use std::ops::{Add, Mul, Sub};
+
+/// A point or displacement with `N` coordinates, the first being x.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VecN<T, const N: usize>(pub [T; N]);
+
+pub type Vec2<T> = VecN<T, 2>;
+
+impl<T: Copy> Vec2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        VecN([x, y])
+    }
+
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+}
+
+impl<const N: usize> VecN<isize, N> {
+    pub fn is_inside(&self, size: &VecN<usize, N>) -> bool {
+        self.0
+            .iter()
+            .zip(&size.0)
+            .all(|(c, size)| usize::try_from(*c).is_ok_and(|c| c < *size))
+    }
+
+    /// The shortest step in the same direction that still lands on grid
+    /// points.
+    pub fn primitive(&self) -> Self {
+        let divisor = self
+            .0
+            .iter()
+            .fold(0, |d, c| gcd(d, c.unsigned_abs()))
+            .max(1) as isize;
+
+        VecN(self.0.map(|c| c / divisor))
+    }
+
+    /// The number of steps along the axes between two points.
+    pub fn manhattan_distance(&self, other: &Self) -> usize {
+        (*self - *other).0.iter().map(|c| c.unsigned_abs()).sum()
+    }
+
+    /// Every point inside a grid of `size`, in the order they're drawn.
+    pub fn points(size: &VecN<usize, N>) -> impl Iterator<Item = Self> + '_ {
+        (0..size.0.iter().product()).map(|mut index: usize| {
+            VecN(std::array::from_fn(|i| {
+                let c = index % size.0[i];
+                index /= size.0[i];
+                c as isize
+            }))
+        })
+    }
+
+    /// Where the point is in a flat list of every point inside a grid of
+    /// `size`, which it must be inside.
+    pub fn index(&self, size: &VecN<usize, N>) -> usize {
+        self.0
+            .iter()
+            .zip(&size.0)
+            .rev()
+            .fold(0, |index, (c, size)| index * size + *c as usize)
+    }
+}
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl<T: Add<Output = T> + Copy, const N: usize> Add for VecN<T, N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        VecN(std::array::from_fn(|i| self.0[i] + rhs.0[i]))
+    }
+}
+
+impl<T: Sub<Output = T> + Copy, const N: usize> Sub for VecN<T, N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        VecN(std::array::from_fn(|i| self.0[i] - rhs.0[i]))
+    }
+}
+
+impl<T: Mul<Output = T> + Copy, const N: usize> Mul<T> for VecN<T, N> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        VecN(self.0.map(|c| c * rhs))
+    }
+}
@@ -59,27 +59,17 @@ fn parse_dont(text: &mut &str) -> bool {
 }
 
 fn parse_mul(text: &mut &str) -> Option<Mul> {
-    let Some(rest) = text.strip_prefix("mul(") else {
-        return None;
-    };
+    let rest = text.strip_prefix("mul(")?;
     *text = rest;
 
-    let Some(x) = parse_number(text) else {
-        return None;
-    };
+    let x = parse_number(text)?;
 
-    let Some(rest) = text.strip_prefix(",") else {
-        return None;
-    };
+    let rest = text.strip_prefix(",")?;
     *text = rest;
 
-    let Some(y) = parse_number(text) else {
-        return None;
-    };
+    let y = parse_number(text)?;
 
-    let Some(rest) = text.strip_prefix(")") else {
-        return None;
-    };
+    let rest = text.strip_prefix(")")?;
     *text = rest;
 
     Some(Mul { x, y })
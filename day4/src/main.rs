@@ -1,67 +1,134 @@
+use aoc::{Grid, Vec2, NEIGHBOURS_8};
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut input: Vec<Vec<char>> = Vec::new();
-    let mut part_1 = 0;
+    let options = Options::from_args()?;
 
-    // get input and check horizontal matches
-    for line in std::io::stdin().lines() {
-        let line = line?;
-        part_1 += line_xmas(line.chars());
-        input.push(line.chars().collect());
-    }
+    let input = Grid::parse(&std::io::read_to_string(std::io::stdin())?, Some)?;
 
-    let height = input.len();
-    let width = input[0].len();
+    let mut part_1 = 0;
 
-    // check vertical matches
-    for column in 0..width {
-        part_1 += line_xmas(input.iter().map(|line| line[column]));
+    // check horizontal and vertical matches
+    for row in input.rows() {
+        part_1 += line_xmas(row.iter().copied());
+    }
+    for column in input.columns() {
+        part_1 += line_xmas(column.copied());
     }
 
-    // check downward diagonal matches
-    for column in 1..width - 3 {
-        part_1 += line_xmas((column..width).zip(0..height).map(|(x, y)| input[y][x]));
+    // check downward and upward diagonal matches
+    for line in input.diagonals() {
+        part_1 += line_xmas(line.copied());
     }
-    for row in 0..height - 3 {
-        part_1 += line_xmas((0..width).zip(row..height).map(|(x, y)| input[y][x]));
+    for line in input.anti_diagonals() {
+        part_1 += line_xmas(line.copied());
     }
 
-    // check upward diagonal matches
-    for column in 3..width - 1 {
-        part_1 += line_xmas((0..=column).rev().zip(0..height).map(|(x, y)| input[y][x]));
+    let mut crosses = Vec::new();
+
+    for centre in input.find(&'A') {
+        let corner = |dx, dy| input.get(centre + Vec2::new(dx, dy)).copied();
+
+        match (corner(-1, -1), corner(1, -1), corner(-1, 1), corner(1, 1)) {
+            (Some('M'), Some('M'), Some('S'), Some('S'))
+            | (Some('M'), Some('S'), Some('M'), Some('S'))
+            | (Some('S'), Some('M'), Some('S'), Some('M'))
+            | (Some('S'), Some('S'), Some('M'), Some('M')) => {
+                crosses.push(
+                    [(0, 0), (-1, -1), (1, -1), (-1, 1), (1, 1)]
+                        .map(|(dx, dy)| centre + Vec2::new(dx, dy))
+                        .to_vec(),
+                );
+            }
+            _ => {}
+        }
     }
-    for row in 0..height - 3 {
-        part_1 += line_xmas((0..width).rev().zip(row..height).map(|(x, y)| input[y][x]));
+
+    let part_2 = crosses.len();
+
+    println!("Part 1: {part_1}");
+    println!("Part 2: {part_2}");
+
+    match options.render {
+        Some(Part::One) => print!("{}", render(&input, &xmas_matches(&input), options.colour)),
+        Some(Part::Two) => print!("{}", render(&input, &crosses, options.colour)),
+        None => {}
     }
 
-    let mut part_2 = 0;
+    Ok(())
+}
 
-    for line in 1..height - 1 {
-        'x: for column in 1..width - 1 {
-            if input[line][column] != 'A' {
-                continue 'x;
-            }
+enum Part {
+    One,
+    Two,
+}
+
+#[derive(Default)]
+struct Options {
+    render: Option<Part>,
+    colour: bool,
+}
 
-            let top_left = input[line - 1][column - 1];
-            let top_right = input[line - 1][column + 1];
-            let bottom_left = input[line + 1][column - 1];
-            let bottom_right = input[line + 1][column + 1];
-
-            match (top_left, top_right, bottom_left, bottom_right) {
-                ('M', 'M', 'S', 'S')
-                | ('M', 'S', 'M', 'S')
-                | ('S', 'M', 'S', 'M')
-                | ('S', 'S', 'M', 'M') => {
-                    part_2 += 1;
+impl Options {
+    fn from_args() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut options = Self::default();
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--render" => {
+                    options.render = Some(match args.next().as_deref() {
+                        Some("1") => Part::One,
+                        Some("2") => Part::Two,
+                        _ => return Err("--render expects a part number (1 or 2)".into()),
+                    });
                 }
-                _ => {}
+                "--colour" | "--color" => options.colour = true,
+                other => return Err(format!("unrecognized argument: {other}").into()),
             }
         }
+
+        Ok(options)
     }
+}
 
-    println!("Part 1: {part_1}");
-    println!("Part 2: {part_2}");
+/// Every horizontal, vertical or diagonal occurrence of XMAS (in either
+/// direction), as the positions of its four letters.
+fn xmas_matches(input: &Grid<char>) -> Vec<Vec<Vec2<isize>>> {
+    let mut matches = Vec::new();
 
-    Ok(())
+    for start in input.find(&'X') {
+        for step in NEIGHBOURS_8 {
+            if input.line(start, step).take(4).eq(&['X', 'M', 'A', 'S']) {
+                matches.push((0..4).map(|i| start + step * i).collect());
+            }
+        }
+    }
+
+    matches
+}
+
+/// Render the word search with every letter that doesn't belong to one of
+/// `matches` replaced by `.`, optionally colouring each match differently.
+fn render(input: &Grid<char>, matches: &[Vec<Vec2<isize>>], colour: bool) -> String {
+    const PALETTE: [u8; 6] = [31, 32, 33, 34, 35, 36];
+
+    let mut owner = input.map(|_| None);
+    for (i, cells) in matches.iter().enumerate() {
+        for &position in cells {
+            owner[position].get_or_insert(i);
+        }
+    }
+
+    let mut rendered = input.map(|_| String::new());
+    for (position, c) in input.iter() {
+        rendered[position] = match owner[position] {
+            Some(i) if colour => format!("\x1b[{}m{c}\x1b[0m", PALETTE[i % PALETTE.len()]),
+            Some(_) => c.to_string(),
+            None => ".".to_string(),
+        };
+    }
+
+    rendered.to_string()
 }
 
 fn line_xmas<I>(line: I) -> usize
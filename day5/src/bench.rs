@@ -0,0 +1,84 @@
+//! Timing comparison between checking updates by scanning every rule and
+//! looking pairs up in a [`RuleSet`], on a synthetic set of rules that totally
+//! orders `0..pages`.
+
+use std::time::Instant;
+
+use crate::{Rule, RuleSet, Update};
+
+const UPDATES: usize = 200;
+const UPDATE_LENGTH: usize = 23;
+
+pub fn run(pages: u32) {
+    let rules = (0..pages)
+        .flat_map(|a| (a + 1..pages).map(move |b| Rule { a, b }))
+        .collect::<Vec<_>>();
+
+    let mut random = Lcg(0x2024_1205);
+    let updates = (0..UPDATES)
+        .map(|_| {
+            let mut candidates = (0..pages).collect::<Vec<_>>();
+            let length = UPDATE_LENGTH.min(candidates.len());
+
+            for i in 0..length {
+                let j = i + random.next() as usize % (candidates.len() - i);
+                candidates.swap(i, j);
+            }
+
+            candidates.truncate(length);
+            Update { pages: candidates }
+        })
+        .collect::<Vec<_>>();
+
+    println!("{} rules, {} updates", rules.len(), updates.len());
+
+    let start = Instant::now();
+    let linear_valid = updates
+        .iter()
+        .filter(|update| is_valid_linear(update, &rules))
+        .count();
+    let linear = start.elapsed();
+    println!("Linear scan: {linear:?}");
+
+    let start = Instant::now();
+    let rule_set = RuleSet::from_rules(&rules);
+    let build = start.elapsed();
+
+    let start = Instant::now();
+    let indexed_valid = updates
+        .iter()
+        .filter(|update| update.is_valid(&rule_set))
+        .count();
+    let indexed = start.elapsed();
+    println!("RuleSet: {indexed:?} (plus {build:?} to build)");
+
+    assert_eq!(linear_valid, indexed_valid);
+
+    println!(
+        "Speedup: {:.1}x",
+        linear.as_secs_f64() / indexed.as_secs_f64().max(f64::MIN_POSITIVE)
+    );
+}
+
+fn is_valid_linear(update: &Update, rules: &[Rule]) -> bool {
+    update.pages.iter().enumerate().all(|(i, preceding_page)| {
+        update.pages[i + 1..].iter().all(|subsequent_page| {
+            !rules
+                .iter()
+                .any(|rule| rule.a == *subsequent_page && rule.b == *preceding_page)
+        })
+    })
+}
+
+/// Just enough randomness to pick pages without pulling in a dependency.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+}
@@ -0,0 +1,174 @@
+//! Checks that the rules pin down exactly one order for each update, which the
+//! puzzle silently assumes when it asks for the middle page.
+
+use std::collections::HashMap;
+
+use crate::{Cycle, RuleSet, Update};
+
+/// The most arrangements of placed pages to count orderings from before
+/// giving up.
+const STATE_BUDGET: usize = 1 << 16;
+
+/// How many ways the pages of an update can be arranged to satisfy the rules.
+pub enum Orderings {
+    None(Cycle),
+    Unique,
+    Multiple(u128),
+    /// Counting took too long, but found more than this many.
+    MoreThan(u128),
+}
+
+impl std::fmt::Display for Orderings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Orderings::None(cycle) => write!(f, "no valid ordering ({cycle})"),
+            Orderings::Unique => f.write_str("unique ordering"),
+            Orderings::Multiple(count) => write!(f, "{count} valid orderings"),
+            Orderings::MoreThan(count) => {
+                write!(f, "more than {count} valid orderings (stopped counting)")
+            }
+        }
+    }
+}
+
+pub fn orderings(update: &Update, rules: &RuleSet) -> Orderings {
+    let sorted = match update.sort(rules) {
+        Ok(sorted) => sorted,
+        Err(cycle) => return Orderings::None(cycle),
+    };
+
+    // a topological order is the only one exactly when every page must come
+    // before the next, as otherwise those two could be swapped (swapping two
+    // copies of a page changes nothing, though)
+    if sorted
+        .pages
+        .windows(2)
+        .all(|pair| pair[0] == pair[1] || rules.precedes(pair[0], pair[1]))
+    {
+        return Orderings::Unique;
+    }
+
+    let pages = &update.pages;
+    if pages.len() > u128::BITS as usize {
+        return Orderings::MoreThan(1);
+    }
+
+    let predecessors = pages
+        .iter()
+        .map(|page| {
+            pages
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| rules.precedes(**other, *page))
+                .fold(0u128, |mask, (i, _)| mask | 1 << i)
+        })
+        .collect::<Vec<_>>();
+
+    let all = u128::MAX >> (u128::BITS as usize - pages.len());
+
+    // copies of a page are counted as different pages, so every ordering is
+    // counted once for each way of arranging the copies among themselves
+    let mut copies = HashMap::new();
+    for page in pages {
+        *copies.entry(*page).or_insert(0u128) += 1;
+    }
+    let arrangements = copies
+        .values()
+        .flat_map(|copies| 1..=*copies)
+        .fold(1u128, u128::saturating_mul);
+
+    match count(0, all, &predecessors, &mut HashMap::new()) {
+        // the count saturated, so there are at least that many
+        Ok(u128::MAX) => Orderings::MoreThan(u128::MAX / arrangements - 1),
+        Ok(count) => Orderings::Multiple(count / arrangements),
+        // there are at least two, or the order would have been unique
+        Err(found) => Orderings::MoreThan((found / arrangements).max(2) - 1),
+    }
+}
+
+/// Count the ways to finish an ordering that has already placed the pages in
+/// `placed`, by trying each page whose predecessors are all placed next.
+///
+/// Once `memo` holds [`STATE_BUDGET`] entries this gives up, returning how
+/// many orderings it had found so far as an error.
+fn count(
+    placed: u128,
+    all: u128,
+    predecessors: &[u128],
+    memo: &mut HashMap<u128, u128>,
+) -> Result<u128, u128> {
+    if placed == all {
+        return Ok(1);
+    }
+
+    if let Some(count) = memo.get(&placed) {
+        return Ok(*count);
+    }
+
+    if memo.len() >= STATE_BUDGET {
+        return Err(0);
+    }
+
+    let mut total = 0u128;
+    for (i, required) in predecessors.iter().enumerate() {
+        if placed & 1 << i == 0 && required & !placed == 0 {
+            match count(placed | 1 << i, all, predecessors, memo) {
+                Ok(count) => total = total.saturating_add(count),
+                Err(found) => return Err(total.saturating_add(found)),
+            }
+        }
+    }
+
+    memo.insert(placed, total);
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{parse, SAMPLE};
+
+    /// How many orderings the sample's rules allow for `update`.
+    fn sample_orderings(update: &str) -> String {
+        let (rules, _) = parse(SAMPLE);
+        let update = update.parse().expect("an update");
+        orderings(&update, &rules).to_string()
+    }
+
+    #[test]
+    fn sample_updates_are_unique() {
+        let (rules, updates) = parse(SAMPLE);
+        for update in &updates {
+            assert_eq!(orderings(update, &rules).to_string(), "unique ordering");
+        }
+    }
+
+    #[test]
+    fn unconstrained_pages_give_multiple_orderings() {
+        // 53 and 12 are unrelated to 75, but 75 must come before 53
+        assert_eq!(sample_orderings("53,12,75"), "3 valid orderings");
+
+        let (rules, updates) = parse("1|2\n1|3\n\n3,2,1\n");
+        assert_eq!(
+            orderings(&updates[0], &rules).to_string(),
+            "2 valid orderings"
+        );
+    }
+
+    #[test]
+    fn cycles_have_no_orderings() {
+        let (rules, updates) = parse("1|2\n2|3\n3|1\n\n1,2,3\n");
+        assert_eq!(
+            orderings(&updates[0], &rules).to_string(),
+            "no valid ordering (rules contain a cycle: 2 -> 3 -> 1 -> 2)"
+        );
+    }
+
+    #[test]
+    fn repeated_pages_are_not_told_apart() {
+        assert_eq!(sample_orderings("61,13,29,13"), "unique ordering");
+        assert_eq!(sample_orderings("13,12,13"), "3 valid orderings");
+        // 61 must come before 29, leaving 5 choose 2 places for them
+        assert_eq!(sample_orderings("12,12,12,29,61"), "10 valid orderings");
+    }
+}
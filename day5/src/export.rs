@@ -0,0 +1,74 @@
+//! Export of the rule graph as text for standard graph rendering tools.
+
+use std::{collections::HashSet, io::Write};
+
+use crate::RuleSet;
+
+#[derive(Clone, Copy)]
+pub enum Format {
+    Dot,
+    Mermaid,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Self::Dot),
+            "mermaid" => Ok(Self::Mermaid),
+            other => Err(format!(
+                "unknown export format {other:?} (expected dot or mermaid)"
+            )),
+        }
+    }
+}
+
+/// Write the subgraph of `rules` induced by `pages`, drawing the edges in
+/// `highlighted` (as `(a, b)` pairs for rules `a|b`) in red.
+pub fn write(
+    out: &mut impl Write,
+    format: Format,
+    rules: &RuleSet,
+    pages: &[u32],
+    highlighted: &HashSet<(u32, u32)>,
+) -> std::io::Result<()> {
+    let edges = pages
+        .iter()
+        .flat_map(|a| pages.iter().map(move |b| (*a, *b)))
+        .filter(|(a, b)| rules.precedes(*a, *b))
+        .collect::<Vec<_>>();
+
+    match format {
+        Format::Dot => {
+            writeln!(out, "digraph rules {{")?;
+            for page in pages {
+                writeln!(out, "    {page};")?;
+            }
+            for (a, b) in edges {
+                if highlighted.contains(&(a, b)) {
+                    writeln!(out, "    {a} -> {b} [color=red, penwidth=2];")?;
+                } else {
+                    writeln!(out, "    {a} -> {b};")?;
+                }
+            }
+            writeln!(out, "}}")?;
+        }
+        Format::Mermaid => {
+            writeln!(out, "flowchart LR")?;
+            for page in pages {
+                writeln!(out, "    p{page}[{page}]")?;
+            }
+            for (a, b) in &edges {
+                writeln!(out, "    p{a} --> p{b}")?;
+            }
+            for (i, edge) in edges.iter().enumerate() {
+                if highlighted.contains(edge) {
+                    writeln!(out, "    linkStyle {i} stroke:red,stroke-width:2px")?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,10 +1,23 @@
+use std::collections::{HashMap, HashSet};
+
+mod bench;
+mod check;
+mod export;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let options = Options::from_args()?;
+
+    if let Some(pages) = options.bench {
+        bench::run(pages);
+        return Ok(());
+    }
+
     let mut input = std::io::stdin().lines();
 
     let mut rules = Vec::new();
     let mut updates = Vec::new();
 
-    while let Some(line) = input.next() {
+    for line in input.by_ref() {
         let line = line?;
 
         if line.is_empty() {
@@ -18,27 +31,76 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         updates.push(line?.parse::<Update>()?);
     }
 
+    let rules = RuleSet::from_rules(&rules);
+
+    if let Some(format) = options.export {
+        let (pages, violating) = match options.update {
+            Some(n) => {
+                let update = n
+                    .checked_sub(1)
+                    .and_then(|i| updates.get(i))
+                    .ok_or_else(|| format!("there is no update number {n}"))?;
+                (update.pages.clone(), vec![update])
+            }
+            None => (rules.pages(), updates.iter().collect()),
+        };
+
+        let highlighted = violating
+            .into_iter()
+            .flat_map(|update| update.violations(&rules))
+            .map(|violation| (violation.rule.a, violation.rule.b))
+            .collect();
+
+        export::write(
+            &mut std::io::stdout().lock(),
+            format,
+            &rules,
+            &pages,
+            &highlighted,
+        )?;
+
+        return Ok(());
+    }
+
+    if options.check {
+        for (update_nr, update) in updates.iter().enumerate() {
+            print!(
+                "Update {}: {}",
+                update_nr + 1,
+                check::orderings(update, &rules)
+            );
+            if update.pages.len() % 2 == 0 {
+                print!("; even length, middle page is ambiguous");
+            }
+            println!();
+        }
+    }
+
     let mut part_1 = 0;
     let mut part_2 = 0;
 
-    'updates: for update in &updates {
-        let len = update.pages.len();
+    for (update_nr, update) in updates.iter().enumerate() {
+        if update.is_valid(&rules) {
+            part_1 += update.middle_page();
+            continue;
+        }
 
-        for (preceding_page, subsequent_page) in (0..len).flat_map(|this| {
-            (this + 1..len).map(move |other| (update.pages[this], update.pages[other]))
-        }) {
-            // find a rule that disallows this ordering
-            for rule in &rules {
-                if rule.a == subsequent_page && rule.b == preceding_page {
-                    let sorted_update = update.sort(&rules);
-                    part_2 += sorted_update.middle_page();
+        if options.explain {
+            println!("Update {}: {update}", update_nr + 1);
+            for violation in update.violations(&rules) {
+                println!("  {violation}");
+            }
+        }
 
-                    continue 'updates;
+        match update.sort(&rules) {
+            Ok(sorted_update) => {
+                if options.explain {
+                    println!("  corrected: {sorted_update}");
                 }
+                part_2 += sorted_update.middle_page();
             }
+            Err(cycle) => eprintln!("Update {}: {cycle}", update_nr + 1),
         }
-
-        part_1 += update.middle_page();
     }
 
     println!("Part 1: {part_1}");
@@ -47,11 +109,59 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[derive(Default)]
+struct Options {
+    bench: Option<u32>,
+    check: bool,
+    explain: bool,
+    export: Option<export::Format>,
+    update: Option<usize>,
+}
+
+impl Options {
+    fn from_args() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut options = Self::default();
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--bench" => {
+                    let pages = args.next().ok_or("--bench expects a number of pages")?;
+                    options.bench = Some(pages.parse()?);
+                }
+                "--check" => options.check = true,
+                "--explain" => options.explain = true,
+                "--export" => {
+                    let format = args.next().ok_or("--export expects a format")?;
+                    options.export = Some(format.parse()?);
+                }
+                "--update" => {
+                    let update = args.next().ok_or("--update expects an update number")?;
+                    options.update = Some(update.parse()?);
+                }
+                other => return Err(format!("unrecognized argument: {other}").into()),
+            }
+        }
+
+        if options.update.is_some() && options.export.is_none() {
+            return Err("--update only applies to --export".into());
+        }
+
+        Ok(options)
+    }
+}
+
 struct Rule {
     a: u32,
     b: u32,
 }
 
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}|{}", self.a, self.b)
+    }
+}
+
 impl std::str::FromStr for Rule {
     type Err = ParseError;
 
@@ -98,28 +208,277 @@ impl std::str::FromStr for Update {
     }
 }
 
+impl std::fmt::Display for Update {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, page) in self.pages.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{page}")?;
+        }
+        Ok(())
+    }
+}
+
 impl Update {
+    /// Whether no pair of pages appears in an order forbidden by a rule.
+    fn is_valid(&self, rules: &RuleSet) -> bool {
+        self.violations(rules).next().is_none()
+    }
+
+    /// Every rule broken by this update, i.e. every pair of pages where the
+    /// later one is required to come before the earlier one.
+    fn violations<'a>(&'a self, rules: &'a RuleSet) -> impl Iterator<Item = Violation> + 'a {
+        self.pages
+            .iter()
+            .enumerate()
+            .flat_map(move |(b_position, b)| {
+                self.pages[b_position + 1..]
+                    .iter()
+                    .enumerate()
+                    .map(move |(offset, a)| (b_position + 1 + offset, *a, b_position, *b))
+            })
+            .filter(|(_, a, _, b)| rules.precedes(*a, *b))
+            .map(|(a_position, a, b_position, b)| Violation {
+                rule: Rule { a, b },
+                a_position,
+                b_position,
+            })
+    }
+
     fn middle_page(&self) -> u32 {
         self.pages[self.pages.len() / 2]
     }
 
-    fn sort(&self, rules: &[Rule]) -> Self {
-        let mut pages = self.pages.clone();
-        let len = pages.len();
+    /// Reorder the pages so that every applicable rule is satisfied, using a
+    /// topological sort of the rules restricted to the pages in this update.
+    ///
+    /// Pages that aren't constrained relative to each other keep their
+    /// original relative order.
+    fn sort(&self, rules: &RuleSet) -> Result<Self, Cycle> {
+        // keyed by index rather than page, as a page may appear twice
+        let precedes = |i: usize, j: usize| rules.precedes(self.pages[i], self.pages[j]);
+        let n = self.pages.len();
+
+        let mut in_degree = (0..n)
+            .map(|j| Some((0..n).filter(|i| precedes(*i, j)).count()))
+            .collect::<Vec<_>>();
+
+        let mut pages = Vec::with_capacity(n);
 
-        for a_idx in 0..len - 1 {
-            for b_idx in a_idx..len {
-                let a = pages[a_idx];
-                let b = pages[b_idx];
+        while pages.len() < n {
+            let Some(next) = in_degree.iter().position(|count| *count == Some(0)) else {
+                let remaining = (0..n)
+                    .filter(|i| in_degree[*i].is_some())
+                    .map(|i| self.pages[i])
+                    .collect::<Vec<_>>();
+                return Err(rules.find_cycle(&remaining));
+            };
 
-                for rule in rules {
-                    if rule.b == a && rule.a == b {
-                        pages.swap(a_idx, b_idx);
-                    }
+            in_degree[next] = None;
+            pages.push(self.pages[next]);
+
+            for (j, count) in in_degree.iter_mut().enumerate() {
+                if let Some(count) = count.as_mut().filter(|_| precedes(next, j)) {
+                    *count -= 1;
                 }
             }
         }
 
-        Self { pages }
+        Ok(Self { pages })
+    }
+}
+
+/// A rule `a|b` that an update breaks by placing page `a` after page `b`.
+struct Violation {
+    rule: Rule,
+    a_position: usize,
+    b_position: usize,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "rule {} violated: {} at position {}, {} at position {}",
+            self.rule,
+            self.rule.a,
+            self.a_position + 1,
+            self.rule.b,
+            self.b_position + 1,
+        )
+    }
+}
+
+/// The page ordering rules, indexed for constant-time lookup. This doubles as
+/// a directed graph with an edge from `a` to `b` for every rule `a|b`.
+struct RuleSet {
+    successors: HashMap<u32, HashSet<u32>>,
+}
+
+impl RuleSet {
+    fn from_rules(rules: &[Rule]) -> Self {
+        let mut successors: HashMap<u32, HashSet<u32>> = HashMap::new();
+
+        for rule in rules {
+            successors.entry(rule.a).or_default().insert(rule.b);
+        }
+
+        Self { successors }
+    }
+
+    /// Whether some rule requires `a` to come before `b`.
+    fn precedes(&self, a: u32, b: u32) -> bool {
+        self.successors.get(&a).is_some_and(|s| s.contains(&b))
+    }
+
+    /// Every page mentioned by a rule, in ascending order.
+    fn pages(&self) -> Vec<u32> {
+        let mut pages = self
+            .successors
+            .iter()
+            .flat_map(|(a, successors)| std::iter::once(a).chain(successors))
+            .copied()
+            .collect::<Vec<_>>();
+        pages.sort_unstable();
+        pages.dedup();
+        pages
+    }
+
+    /// Find a cycle in the subgraph induced by `pages`, in which every page
+    /// must have a predecessor (as is the case for whatever is left over when
+    /// a topological sort gets stuck).
+    fn find_cycle(&self, pages: &[u32]) -> Cycle {
+        let mut path = vec![pages[0]];
+
+        loop {
+            let current = path[path.len() - 1];
+            let previous = *pages
+                .iter()
+                .find(|p| self.precedes(**p, current))
+                .expect("every page in a cyclic remainder has a predecessor");
+
+            if let Some(start) = path.iter().position(|p| *p == previous) {
+                let mut pages = path.split_off(start);
+                pages.reverse();
+                return Cycle { pages };
+            }
+
+            path.push(previous);
+        }
+    }
+}
+
+/// A set of rules that require each page to come before the next, and the
+/// last before the first.
+#[derive(Debug)]
+struct Cycle {
+    pages: Vec<u32>,
+}
+
+impl std::fmt::Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("rules contain a cycle: ")?;
+        for page in &self.pages {
+            write!(f, "{page} -> ")?;
+        }
+        write!(f, "{}", self.pages[0])
+    }
+}
+
+impl std::error::Error for Cycle {}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// The example from the puzzle.
+    pub(crate) const SAMPLE: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+";
+
+    /// The rules and updates in `input`, laid out like the puzzle input.
+    pub(crate) fn parse(input: &str) -> (RuleSet, Vec<Update>) {
+        let (rules, updates) = input.split_once("\n\n").expect("a blank line");
+        let rules = rules
+            .lines()
+            .map(|line| line.parse().expect("a rule"))
+            .collect::<Vec<_>>();
+        let updates = updates
+            .lines()
+            .map(|line| line.parse().expect("an update"))
+            .collect();
+
+        (RuleSet::from_rules(&rules), updates)
+    }
+
+    fn sorted(rules: &str, update: &str) -> Result<Vec<u32>, Cycle> {
+        let (rules, updates) = parse(&format!("{rules}\n\n{update}\n"));
+        updates[0].sort(&rules).map(|update| update.pages)
+    }
+
+    #[test]
+    fn sorts_the_sample_corrections() {
+        let (rules, updates) = parse(SAMPLE);
+        let corrected = updates
+            .iter()
+            .filter(|update| !update.is_valid(&rules))
+            .map(|update| update.sort(&rules).expect("no cycle").pages)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            corrected,
+            [
+                vec![97, 75, 47, 61, 53],
+                vec![61, 29, 13],
+                vec![97, 75, 47, 29, 13],
+            ]
+        );
+    }
+
+    #[test]
+    fn sorts_repeated_pages() {
+        assert_eq!(sorted("1|2", "2,1,1").unwrap(), [1, 1, 2]);
+    }
+
+    #[test]
+    fn keeps_unconstrained_pages_in_order() {
+        assert_eq!(sorted("1|2", "5,3,4").unwrap(), [5, 3, 4]);
+        assert_eq!(sorted("1|2", "3,2,5,1").unwrap(), [3, 5, 1, 2]);
+    }
+
+    #[test]
+    fn reports_a_cycle() {
+        let cycle = sorted("1|2\n2|3\n3|1", "4,1,2,3").unwrap_err();
+
+        assert_eq!(cycle.pages, [2, 3, 1]);
+        assert_eq!(cycle.to_string(), "rules contain a cycle: 2 -> 3 -> 1 -> 2");
     }
 }
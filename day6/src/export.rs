@@ -0,0 +1,101 @@
+//! Output of every obstruction that would trap a guard in a loop.
+
+use std::io::Write;
+
+use crate::{Lab, PatrolLoop, Position};
+
+#[derive(Clone, Copy)]
+pub enum Format {
+    List,
+    Grid,
+    Csv,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "list" => Ok(Self::List),
+            "grid" => Ok(Self::Grid),
+            "csv" => Ok(Self::Csv),
+            other => Err(format!(
+                "unknown loop format {other:?} (expected list, grid or csv)"
+            )),
+        }
+    }
+}
+
+pub struct LoopingObstruction {
+    pub position: (usize, usize),
+    /// The first guard to get stuck because of this obstruction.
+    pub guard: usize,
+    pub patrol_loop: PatrolLoop,
+}
+
+/// Write `loops`, either as one line per obstruction or marked with `O` on a
+/// map of the lab like the puzzle's examples, with every guard where it
+/// starts.
+pub fn write(
+    out: &mut impl Write,
+    format: Format,
+    labs: &[Lab],
+    loops: &[LoopingObstruction],
+) -> std::io::Result<()> {
+    match format {
+        Format::List => {
+            for l in loops {
+                let (x, y) = l.position;
+                writeln!(
+                    out,
+                    "({x}, {y}): guard {} loops after {} steps through {} cells",
+                    l.guard + 1,
+                    l.patrol_loop.length,
+                    l.patrol_loop.cells.len(),
+                )?;
+            }
+            writeln!(out, "{} obstructions", loops.len())?;
+        }
+        Format::Grid => {
+            let lab = &labs[0];
+            for y in 0..lab.cells.height() {
+                for x in 0..lab.cells.width() {
+                    let guard = labs.iter().find(|lab| lab.position == (x, y));
+
+                    let glyph = if loops.iter().any(|l| l.position == (x, y)) {
+                        'O'
+                    } else if let Some(guard) = guard {
+                        guard.direction.glyph()
+                    } else if matches!(lab.cell(x, y), Position::Obstructed) {
+                        '#'
+                    } else {
+                        '.'
+                    };
+                    write!(out, "{glyph}")?;
+                }
+                writeln!(out)?;
+            }
+        }
+        Format::Csv => {
+            writeln!(out, "x,y,guard,loop_length,loop_cells")?;
+            for l in loops {
+                let (x, y) = l.position;
+                let cells = l
+                    .patrol_loop
+                    .cells
+                    .iter()
+                    .map(|(x, y)| format!("{x}:{y}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                writeln!(
+                    out,
+                    "{x},{y},{},{},{cells}",
+                    l.guard + 1,
+                    l.patrol_loop.length
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
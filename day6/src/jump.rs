@@ -0,0 +1,139 @@
+//! Obstacle-to-obstacle guard simulation for finding loops quickly.
+
+use crate::{Direction, Directions, Lab, Position};
+
+/// Where the guard stops when walking from a cell in some direction.
+#[derive(Clone, Copy)]
+enum Stop {
+    /// In front of an obstruction at the given cell.
+    Before(usize, usize),
+    /// Off the edge of the lab.
+    Exit,
+}
+
+/// For every cell and direction, the next stop the guard reaches walking that
+/// way, so a patrol can be followed without visiting every cell in between.
+pub struct JumpTable {
+    width: usize,
+    stops: Box<[[Stop; 4]]>,
+}
+
+impl JumpTable {
+    pub fn new(lab: &Lab) -> Self {
+        let (width, height) = (lab.cells.width(), lab.cells.height());
+        let mut stops = vec![[Stop::Exit; 4]; width * height].into_boxed_slice();
+        let obstructed = |x: usize, y: usize| matches!(lab.cell(x, y), Position::Obstructed);
+
+        for x in 0..width {
+            let mut stop = Stop::Exit;
+            for y in 0..height {
+                if obstructed(x, y) {
+                    stop = Stop::Before(x, y + 1);
+                }
+                stops[y * width + x][Direction::Up as usize] = stop;
+            }
+
+            let mut stop = Stop::Exit;
+            for y in (0..height).rev() {
+                if obstructed(x, y) {
+                    stop = y.checked_sub(1).map_or(Stop::Exit, |y| Stop::Before(x, y));
+                }
+                stops[y * width + x][Direction::Down as usize] = stop;
+            }
+        }
+
+        for y in 0..height {
+            let mut stop = Stop::Exit;
+            for x in 0..width {
+                if obstructed(x, y) {
+                    stop = Stop::Before(x + 1, y);
+                }
+                stops[y * width + x][Direction::Left as usize] = stop;
+            }
+
+            let mut stop = Stop::Exit;
+            for x in (0..width).rev() {
+                if obstructed(x, y) {
+                    stop = x.checked_sub(1).map_or(Stop::Exit, |x| Stop::Before(x, y));
+                }
+                stops[y * width + x][Direction::Right as usize] = stop;
+            }
+        }
+
+        Self { width, stops }
+    }
+
+    /// Where the guard stops walking from `(x, y)` in `direction`, taking into
+    /// account one extra obstruction placed at `extra`.
+    fn next_stop(
+        &self,
+        (x, y): (usize, usize),
+        direction: Direction,
+        extra: (usize, usize),
+    ) -> Stop {
+        let stop = self.stops[y * self.width + x][direction as usize];
+        let (ex, ey) = extra;
+
+        // the extra obstruction is in the way if it lies between the guard and
+        // wherever it would otherwise have stopped
+        let blocked = match (direction, stop) {
+            (Direction::Up, Stop::Before(_, sy)) => ex == x && ey < y && ey >= sy,
+            (Direction::Up, Stop::Exit) => ex == x && ey < y,
+            (Direction::Down, Stop::Before(_, sy)) => ex == x && ey > y && ey <= sy,
+            (Direction::Down, Stop::Exit) => ex == x && ey > y,
+            (Direction::Left, Stop::Before(sx, _)) => ey == y && ex < x && ex >= sx,
+            (Direction::Left, Stop::Exit) => ey == y && ex < x,
+            (Direction::Right, Stop::Before(sx, _)) => ey == y && ex > x && ex <= sx,
+            (Direction::Right, Stop::Exit) => ey == y && ex > x,
+        };
+
+        if !blocked {
+            return stop;
+        }
+
+        match direction {
+            Direction::Up => Stop::Before(ex, ey + 1),
+            Direction::Down => Stop::Before(ex, ey - 1),
+            Direction::Left => Stop::Before(ex + 1, ey),
+            Direction::Right => Stop::Before(ex - 1, ey),
+        }
+    }
+
+    /// Whether the guard in `lab` patrols forever once an obstruction is
+    /// added at `extra`. The lab must have bounded topology.
+    ///
+    /// `seen` is scratch space with one entry per cell, which must be all
+    /// zeroes and is left that way.
+    pub fn loops(&self, lab: &Lab, extra: (usize, usize), seen: &mut [u8]) -> bool {
+        let mut position = lab.position;
+        let mut direction = lab.direction;
+        let mut turns = lab.turns;
+        let mut touched = Vec::new();
+
+        let looped = loop {
+            let Stop::Before(x, y) = self.next_stop(position, direction, extra) else {
+                break false;
+            };
+
+            let index = y * self.width + x;
+            let bit = Directions::new(direction, lab.turning.phase(turns)).0;
+            if seen[index] & bit != 0 {
+                break true;
+            }
+            if seen[index] == 0 {
+                touched.push(index);
+            }
+            seen[index] |= bit;
+
+            position = (x, y);
+            lab.turning.turn(&mut direction, turns);
+            turns += 1;
+        };
+
+        for index in touched {
+            seen[index] = 0;
+        }
+
+        looped
+    }
+}
@@ -1,52 +1,241 @@
+use aoc::{Grid, Vec2};
+
+mod export;
+mod jump;
+mod replay;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let lab = Lab::from_stdin()?;
-    let mut lab_pt1 = lab.clone();
+    let options = Options::from_args()?;
+    let labs = Lab::from_stdin(options.turning, options.topology)?;
 
-    let mut part_1 = 1;
-    loop {
-        match lab_pt1.advance() {
-            AdvanceResult::NewPosition => part_1 += 1,
-            AdvanceResult::AlreadyVisited => {}
-            AdvanceResult::Stuck => unreachable!(),
-            AdvanceResult::OutOfBounds => break,
-        }
+    if options.replay {
+        return replay::run(labs, options.fps, options.obstruction);
     }
 
-    println!("Part 1: {part_1}");
+    let mut patrols = labs.clone();
 
-    let mut part_2 = 0;
-    'positions: for (x, y) in (0..lab.width)
-        .flat_map(|x| (0..lab.data.len() / lab.width).map(move |y| (x, y)))
-        .filter(|(x, y)| matches!(lab.cell(*x, *y), Position::Empty))
-    {
-        let mut lab = lab.clone();
-        *lab.cell_mut(x, y) = Position::Obstructed;
+    // every guard moves one step at a time, in turn, until they have all
+    // either left the lab or been found to loop
+    let mut coverage = vec![1; patrols.len()];
+    let mut escapes = vec![None; patrols.len()];
+    while escapes.contains(&None) {
+        for (guard, lab) in patrols.iter_mut().enumerate() {
+            if escapes[guard].is_some() {
+                continue;
+            }
 
-        loop {
             match lab.advance() {
-                AdvanceResult::NewPosition | AdvanceResult::AlreadyVisited => {}
-                AdvanceResult::Stuck => {
-                    part_2 += 1;
-                    continue 'positions;
+                AdvanceResult::NewPosition => coverage[guard] += 1,
+                AdvanceResult::AlreadyVisited => {}
+                AdvanceResult::Loop => {
+                    eprintln!("Guard {} never leaves the lab", guard + 1);
+                    escapes[guard] = Some(false);
                 }
-                AdvanceResult::OutOfBounds => continue 'positions,
+                AdvanceResult::OutOfBounds => escapes[guard] = Some(true),
             }
         }
     }
 
-    println!("Part 2: {part_2}");
+    let visited_by_any = |(x, y): (usize, usize)| {
+        patrols
+            .iter()
+            .any(|lab| matches!(lab.cell(x, y), Position::Visited(_)))
+    };
+
+    let part_1 = labs[0]
+        .positions()
+        .filter(|position| visited_by_any(*position))
+        .count();
+
+    if options.loops.is_none() {
+        println!("Part 1: {part_1}");
+    }
+
+    if labs.len() > 1 && options.loops.is_none() {
+        for (guard, lab) in labs.iter().enumerate() {
+            let (x, y) = lab.position;
+            let outcome = match escapes[guard] {
+                Some(true) => "leaves the lab",
+                _ => "loops",
+            };
+            println!(
+                "  Guard {} at ({x}, {y}): {} cells, {outcome}",
+                guard + 1,
+                coverage[guard]
+            );
+        }
+    }
+
+    if options.topology == Topology::Toroidal {
+        println!("Part 2 only applies to bounded labs");
+        return Ok(());
+    }
+
+    // a guard that loops anyway would loop with an obstruction almost
+    // anywhere, so only guards that would leave count towards part 2
+    let escaping = (0..labs.len())
+        .filter(|guard| escapes[*guard] == Some(true))
+        .collect::<Vec<_>>();
+
+    // an obstruction can only change a patrol if it's somewhere the guard
+    // would otherwise have walked
+    let visited_by_escaping = |(x, y): (usize, usize)| {
+        escaping
+            .iter()
+            .any(|guard| matches!(patrols[*guard].cell(x, y), Position::Visited(_)))
+    };
+
+    let lab = &labs[0];
+    let jumps = jump::JumpTable::new(lab);
+    let mut seen = vec![0; lab.cells.width() * lab.cells.height()];
+
+    let obstructions = lab
+        .positions()
+        .filter(|(x, y)| matches!(lab.cell(*x, *y), Position::Empty))
+        .filter(|position| labs.iter().all(|lab| lab.position != *position))
+        .filter(|position| visited_by_escaping(*position))
+        .filter(|obstruction| {
+            escaping
+                .iter()
+                .any(|guard| jumps.loops(&labs[*guard], *obstruction, &mut seen))
+        })
+        .collect::<Vec<_>>();
+
+    let Some(format) = options.loops else {
+        println!("Part 2: {}", obstructions.len());
+        for guard in (0..labs.len()).filter(|guard| !escaping.contains(guard)) {
+            println!(
+                "  Guard {} loops without an obstruction, so isn't counted",
+                guard + 1
+            );
+        }
+        return Ok(());
+    };
+
+    let loops = obstructions
+        .into_iter()
+        .map(|obstruction| {
+            escaping
+                .iter()
+                .find_map(|guard| {
+                    let patrol_loop = labs[*guard].find_loop(obstruction)?;
+                    Some(export::LoopingObstruction {
+                        position: obstruction,
+                        guard: *guard,
+                        patrol_loop,
+                    })
+                })
+                .expect("some guard loops")
+        })
+        .collect::<Vec<_>>();
+
+    export::write(&mut std::io::stdout().lock(), format, &labs, &loops)?;
 
     Ok(())
 }
 
+struct Options {
+    replay: bool,
+    fps: f64,
+    obstruction: Option<(usize, usize)>,
+    turning: Turning,
+    topology: Topology,
+    loops: Option<export::Format>,
+}
+
+impl Options {
+    fn from_args() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut options = Self {
+            replay: false,
+            fps: 10.0,
+            obstruction: None,
+            turning: Turning::Right,
+            topology: Topology::Bounded,
+            loops: None,
+        };
+        let mut args = std::env::args().skip(1);
+
+        // the first option given that only makes sense with --replay
+        let mut replay_only = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--replay" => options.replay = true,
+                "--fps" => {
+                    replay_only.get_or_insert("--fps");
+                    let fps = args.next().ok_or("--fps expects a frame rate")?;
+                    options.fps = fps.parse()?;
+                    if options.fps.is_nan() || options.fps <= 0.0 {
+                        return Err("--fps must be positive".into());
+                    }
+                }
+                "--obstruction" => {
+                    replay_only.get_or_insert("--obstruction");
+                    let coordinate = args.next().ok_or("--obstruction expects x,y")?;
+                    let (x, y) = coordinate
+                        .split_once(',')
+                        .ok_or("--obstruction expects x,y")?;
+                    options.obstruction = Some((x.trim().parse()?, y.trim().parse()?));
+                }
+                "--turn" => {
+                    let turning = args.next().ok_or("--turn expects a turning policy")?;
+                    options.turning = turning.parse()?;
+                }
+                "--topology" => {
+                    let topology = args.next().ok_or("--topology expects a topology")?;
+                    options.topology = topology.parse()?;
+                }
+                "--loops" => {
+                    let format = args.next().ok_or("--loops expects a format")?;
+                    options.loops = Some(format.parse()?);
+                }
+                other => return Err(format!("unrecognized argument: {other}").into()),
+            }
+        }
+
+        if options.loops.is_some() && options.topology == Topology::Toroidal {
+            return Err("--loops only applies to bounded labs".into());
+        }
+
+        if let Some(arg) = replay_only.filter(|_| !options.replay) {
+            return Err(format!("{arg} only applies to --replay").into());
+        }
+
+        Ok(options)
+    }
+}
+
 #[derive(Clone, Copy)]
 enum Position {
     Empty,
-    Visited(Direction),
+    Visited(Directions),
     Obstructed,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+/// The set of directions the guard has been facing while standing on a cell,
+/// each paired with the phase of its turning policy at the time.
+#[derive(Clone, Copy)]
+struct Directions(u8);
+
+impl Directions {
+    fn new(direction: Direction, phase: u8) -> Self {
+        Self(1 << (direction as u8 + 4 * phase))
+    }
+
+    /// Whether the guard has faced `direction` here, in any phase.
+    fn contains(&self, direction: Direction) -> bool {
+        self.0 & (0x11 << direction as u8) != 0
+    }
+
+    /// Add `direction` to the set, returning whether it was newly inserted.
+    fn insert(&mut self, direction: Direction, phase: u8) -> bool {
+        let previous = self.0;
+        self.0 |= Self::new(direction, phase).0;
+        self.0 != previous
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 enum Direction {
     Up,
     Right,
@@ -55,6 +244,25 @@ enum Direction {
 }
 
 impl Direction {
+    fn from_glyph(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    fn glyph(&self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+
     fn turn(&mut self) {
         *self = match self {
             Direction::Up => Direction::Right,
@@ -63,115 +271,423 @@ impl Direction {
             Direction::Left => Direction::Up,
         };
     }
+
+    fn turn_left(&mut self) {
+        *self = match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        };
+    }
+
+    fn reverse(&mut self) {
+        *self = match self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        };
+    }
+}
+
+/// What the guard does on finding an obstruction in front of it.
+#[derive(Clone, Copy, PartialEq)]
+enum Turning {
+    Right,
+    Left,
+    Reverse,
+    /// Right, then left, then right again and so on.
+    Alternating,
+}
+
+impl Turning {
+    /// Turn `direction` according to this policy, given how many turns the
+    /// guard has already made.
+    fn turn(&self, direction: &mut Direction, turns: usize) {
+        match self {
+            Turning::Right => direction.turn(),
+            Turning::Left => direction.turn_left(),
+            Turning::Reverse => direction.reverse(),
+            Turning::Alternating if turns.is_multiple_of(2) => direction.turn(),
+            Turning::Alternating => direction.turn_left(),
+        }
+    }
+
+    /// The part of the guard's state that the policy depends on, which must
+    /// be taken into account when looking for loops.
+    fn phase(&self, turns: usize) -> u8 {
+        match self {
+            Turning::Alternating => (turns % 2) as u8,
+            _ => 0,
+        }
+    }
+}
+
+impl std::str::FromStr for Turning {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "right" => Ok(Self::Right),
+            "left" => Ok(Self::Left),
+            "reverse" => Ok(Self::Reverse),
+            "alternating" => Ok(Self::Alternating),
+            other => Err(format!(
+                "unknown turning policy {other:?} (expected right, left, reverse or alternating)"
+            )),
+        }
+    }
+}
+
+/// What happens when the guard walks off the edge of the lab.
+///
+/// Part 2 only applies to bounded labs: a guard on a torus can never leave,
+/// so it loops with or without an added obstruction.
+#[derive(Clone, Copy, PartialEq)]
+enum Topology {
+    /// The guard leaves the lab.
+    Bounded,
+    /// The guard reappears on the opposite edge.
+    Toroidal,
+}
+
+impl std::str::FromStr for Topology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bounded" => Ok(Self::Bounded),
+            "toroidal" => Ok(Self::Toroidal),
+            other => Err(format!(
+                "unknown topology {other:?} (expected bounded or toroidal)"
+            )),
+        }
+    }
 }
 
 #[derive(Clone)]
 struct Lab {
     position: (usize, usize),
     direction: Direction,
-    width: usize,
-    data: Box<[Position]>,
+    turns: usize,
+    turning: Turning,
+    topology: Topology,
+    cells: Grid<Position>,
 }
 
 impl Lab {
-    fn from_stdin() -> Result<Self, std::io::Error> {
-        let mut position = (0, 0);
-        let mut width = 0;
-        let mut data = Vec::new();
-
-        for (line_nr, line_text) in std::io::stdin().lines().enumerate() {
-            let line_text = line_text?;
-            width = width.max(line_text.len());
-
-            for (column_nr, c) in line_text.chars().enumerate() {
-                data.push(match c {
-                    '#' => Position::Obstructed,
-                    '^' => {
-                        position = (column_nr, line_nr);
-                        Position::Visited(Direction::Up)
-                    }
-                    _ => Position::Empty,
-                });
-            }
+    /// Read a lab containing any number of guards, each marked by a glyph
+    /// showing which way it's facing, and return a copy of it for each guard
+    /// to patrol.
+    fn from_stdin(
+        turning: Turning,
+        topology: Topology,
+    ) -> Result<Vec<Self>, Box<dyn std::error::Error>> {
+        let input = std::io::read_to_string(std::io::stdin())?;
+        Ok(Self::parse(&input, turning, topology)?)
+    }
+
+    /// Like [`Lab::from_stdin`], but reading the lab from `text`.
+    fn parse(text: &str, turning: Turning, topology: Topology) -> Result<Vec<Self>, String> {
+        let glyphs = Grid::parse(text, Some)?;
+
+        let guards = glyphs
+            .iter()
+            .filter_map(|(position, c)| {
+                let position = (position.x() as usize, position.y() as usize);
+                Some((position, Direction::from_glyph(*c)?))
+            })
+            .collect::<Vec<_>>();
+
+        if guards.is_empty() {
+            return Err("there is no guard in the lab".into());
         }
 
-        Ok(Lab {
-            position,
+        let lab = Lab {
+            position: (0, 0),
             direction: Direction::Up,
-            width,
-            data: data.into_boxed_slice(),
-        })
+            turns: 0,
+            turning,
+            topology,
+            cells: glyphs.map(|c| match c {
+                '#' => Position::Obstructed,
+                _ => Position::Empty,
+            }),
+        };
+
+        Ok(guards
+            .into_iter()
+            .map(|(position, direction)| {
+                let mut lab = lab.clone();
+                lab.position = position;
+                lab.direction = direction;
+                *lab.cell_mut(position.0, position.1) =
+                    Position::Visited(Directions::new(direction, 0));
+                lab
+            })
+            .collect())
     }
 
     fn next_position(&self) -> Option<(usize, usize)> {
         let (x, y) = self.position;
+        let (width, height) = (self.cells.width(), self.cells.height());
+        let wraps = self.topology == Topology::Toroidal;
 
         match &self.direction {
-            Direction::Up => {
-                let y = y.checked_sub(1)?;
-                Some((x, y))
-            }
+            Direction::Up => match y.checked_sub(1) {
+                Some(y) => Some((x, y)),
+                None => wraps.then_some((x, height - 1)),
+            },
             Direction::Right => {
-                if x == self.width {
-                    return None;
+                if x + 1 == width {
+                    return wraps.then_some((0, y));
                 }
                 Some((x + 1, y))
             }
             Direction::Down => {
-                if (y + 1) * self.width >= self.data.len() {
-                    return None;
+                if y + 1 == height {
+                    return wraps.then_some((x, 0));
                 }
                 Some((x, y + 1))
             }
-            Direction::Left => {
-                let x = x.checked_sub(1)?;
-                Some((x, y))
-            }
+            Direction::Left => match x.checked_sub(1) {
+                Some(x) => Some((x, y)),
+                None => wraps.then_some((width - 1, y)),
+            },
         }
     }
 
     fn cell(&self, x: usize, y: usize) -> Position {
-        self.data[y * self.width + x]
+        self.cells[Vec2::new(x as isize, y as isize)]
     }
 
     fn cell_mut(&mut self, x: usize, y: usize) -> &mut Position {
-        &mut self.data[y * self.width + x]
+        &mut self.cells[Vec2::new(x as isize, y as isize)]
+    }
+
+    /// Every cell's position, row by row.
+    fn positions(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.cells
+            .positions()
+            .map(|position| (position.x() as usize, position.y() as usize))
     }
 
+    /// Move the guard one cell, turning first if the way ahead is obstructed.
+    ///
+    /// The guard is in a loop as soon as it stands on a cell facing a
+    /// direction it has already faced there, since everything it does next is
+    /// determined by that state.
     fn advance(&mut self) -> AdvanceResult {
         while let Some((x, y)) = self.next_position() {
             match self.cell(x, y) {
                 Position::Empty => {
-                    *self.cell_mut(x, y) = Position::Visited(self.direction.clone());
+                    *self.cell_mut(x, y) = Position::Visited(Directions::new(
+                        self.direction,
+                        self.turning.phase(self.turns),
+                    ));
                     self.position = (x, y);
                     return AdvanceResult::NewPosition;
                 }
-                Position::Visited(prev_direction) => {
+                Position::Visited(mut directions) => {
                     self.position = (x, y);
 
-                    if prev_direction == self.direction
-                        && self
-                            .next_position()
-                            .is_some_and(|(x, y)| matches!(self.cell(x, y), Position::Obstructed))
-                    {
-                        return AdvanceResult::Stuck;
+                    if !directions.insert(self.direction, self.turning.phase(self.turns)) {
+                        return AdvanceResult::Loop;
                     }
 
+                    *self.cell_mut(x, y) = Position::Visited(directions);
                     return AdvanceResult::AlreadyVisited;
                 }
                 Position::Obstructed => {
-                    self.direction.turn();
+                    self.turning.turn(&mut self.direction, self.turns);
+                    self.turns += 1;
+
+                    let (x, y) = self.position;
+                    let Position::Visited(mut directions) = self.cell(x, y) else {
+                        unreachable!("the guard's position is always visited");
+                    };
+
+                    if !directions.insert(self.direction, self.turning.phase(self.turns)) {
+                        return AdvanceResult::Loop;
+                    }
+
+                    *self.cell_mut(x, y) = Position::Visited(directions);
                 }
             }
         }
 
         AdvanceResult::OutOfBounds
     }
+
+    /// The loop the guard ends up in once an obstruction is added at `(x, y)`,
+    /// if any.
+    fn find_loop(&self, (x, y): (usize, usize)) -> Option<PatrolLoop> {
+        let mut lab = self.clone();
+        *lab.cell_mut(x, y) = Position::Obstructed;
+
+        // the guard's next move only depends on this state, so the loop is
+        // everything between the first time it repeats and the second
+        let state = |lab: &Lab| (lab.position, lab.direction, lab.turning.phase(lab.turns));
+
+        let mut states = vec![state(&lab)];
+        let mut seen = std::collections::HashMap::from([(states[0], 0)]);
+
+        let start = loop {
+            if let AdvanceResult::OutOfBounds = lab.advance() {
+                return None;
+            }
+
+            let next = state(&lab);
+            if let Some(start) = seen.insert(next, states.len()) {
+                states.push(next);
+                break start;
+            }
+            states.push(next);
+        };
+
+        let repeating = &states[start..];
+        let length = repeating.windows(2).filter(|w| w[0].0 != w[1].0).count();
+
+        let mut cells = Vec::new();
+        for (position, _, _) in repeating {
+            if !cells.contains(position) {
+                cells.push(*position);
+            }
+        }
+
+        Some(PatrolLoop { length, cells })
+    }
+}
+
+/// The part of a patrol that repeats forever.
+struct PatrolLoop {
+    /// How many steps the guard takes to get around once.
+    length: usize,
+    /// Every cell the guard stands on, in the order first reached.
+    cells: Vec<(usize, usize)>,
 }
 
 enum AdvanceResult {
     NewPosition,
     AlreadyVisited,
-    Stuck,
+    Loop,
     OutOfBounds,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lab(text: &str, turning: Turning) -> Lab {
+        Lab::parse(text, turning, Topology::Bounded)
+            .expect("a valid lab")
+            .remove(0)
+    }
+
+    /// Walk the guard until it loops or leaves, returning whether it looped
+    /// and how many cells it covered.
+    fn patrol(mut lab: Lab) -> (bool, usize) {
+        let mut cells = 1;
+        loop {
+            match lab.advance() {
+                AdvanceResult::NewPosition => cells += 1,
+                AdvanceResult::AlreadyVisited => {}
+                AdvanceResult::Loop => return (true, cells),
+                AdvanceResult::OutOfBounds => return (false, cells),
+            }
+        }
+    }
+
+    /// Whether the jump table agrees that the guard loops, given somewhere
+    /// already obstructed to pass as the extra obstruction.
+    fn jumps_loop(lab: &Lab, obstructed: (usize, usize)) -> bool {
+        let mut seen = vec![0; lab.cells.width() * lab.cells.height()];
+        jump::JumpTable::new(lab).loops(lab, obstructed, &mut seen)
+    }
+
+    #[test]
+    fn ragged_rows() {
+        assert!(Lab::parse("..#\n.^\n...\n", Turning::Right, Topology::Bounded).is_err());
+    }
+
+    #[test]
+    fn rectangle() {
+        let lab = lab(".#...\n....#\n#^...\n...#.\n", Turning::Right);
+
+        assert_eq!(patrol(lab.clone()), (true, 6));
+        assert!(jumps_loop(&lab, (1, 0)));
+
+        let patrol_loop = lab.find_loop((1, 0)).expect("a loop");
+        assert_eq!(patrol_loop.length, 6);
+        assert_eq!(patrol_loop.cells.len(), 6);
+    }
+
+    #[test]
+    fn loop_entered_mid_path() {
+        let lab = lab(".#...\n....#\n#....\n...#.\n.....\n.^...\n", Turning::Right);
+
+        assert_eq!(patrol(lab.clone()), (true, 9));
+        assert!(jumps_loop(&lab, (1, 0)));
+
+        // the cells walked on the way in aren't part of the loop
+        let patrol_loop = lab.find_loop((1, 0)).expect("a loop");
+        assert_eq!(patrol_loop.length, 6);
+        assert!(!patrol_loop.cells.contains(&(1, 3)));
+        assert!(!patrol_loop.cells.contains(&(1, 4)));
+        assert!(!patrol_loop.cells.contains(&(1, 5)));
+    }
+
+    #[test]
+    fn u_turn() {
+        // turning right twice at each end of a corridor
+        let lab = lab("#####\n#>..#\n#####\n", Turning::Right);
+
+        assert_eq!(patrol(lab.clone()), (true, 3));
+        assert!(jumps_loop(&lab, (0, 0)));
+
+        let patrol_loop = lab.find_loop((0, 0)).expect("a loop");
+        assert_eq!(patrol_loop.length, 4);
+        assert_eq!(patrol_loop.cells.len(), 3);
+    }
+
+    #[test]
+    fn u_turn_by_reversing() {
+        let lab = lab("#>..#\n", Turning::Reverse);
+
+        assert_eq!(patrol(lab.clone()), (true, 3));
+        assert!(jumps_loop(&lab, (0, 0)));
+        assert_eq!(lab.find_loop((0, 0)).expect("a loop").length, 4);
+    }
+
+    #[test]
+    fn boxed_in() {
+        // the guard turns in place without ever moving
+        let lab = lab(".#.\n#^#\n.#.\n", Turning::Right);
+
+        assert_eq!(patrol(lab.clone()), (true, 1));
+        assert!(jumps_loop(&lab, (1, 0)));
+
+        let patrol_loop = lab.find_loop((1, 0)).expect("a loop");
+        assert_eq!(patrol_loop.length, 0);
+        assert_eq!(patrol_loop.cells, [(1, 1)]);
+    }
+
+    #[test]
+    fn escape() {
+        let lab = lab(".#.\n...\n.^.\n", Turning::Right);
+
+        assert_eq!(patrol(lab.clone()), (false, 3));
+        assert!(!jumps_loop(&lab, (1, 0)));
+        assert!(lab.find_loop((1, 0)).is_none());
+    }
+    #[test]
+    fn loop_grid_draws_every_guard() {
+        let labs = Lab::parse("^.#\n.<.\n", Turning::Right, Topology::Bounded).unwrap();
+
+        let mut grid = Vec::new();
+        export::write(&mut grid, export::Format::Grid, &labs, &[]).unwrap();
+
+        assert_eq!(String::from_utf8(grid).unwrap(), "^.#\n.<.\n");
+    }
+}
@@ -0,0 +1,117 @@
+//! Step-by-step terminal animation of the guards' patrol.
+
+use std::{thread, time::Duration};
+
+use crate::{AdvanceResult, Direction, Directions, Lab, Position};
+
+/// Animate the guards patrolling `labs` until every one of them has either
+/// left or been found to loop, optionally with an extra obstruction (drawn as
+/// `O`) placed beforehand.
+pub fn run(
+    mut labs: Vec<Lab>,
+    fps: f64,
+    obstruction: Option<(usize, usize)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some((x, y)) = obstruction {
+        let lab = &labs[0];
+        if x >= lab.cells.width() || y >= lab.cells.height() {
+            return Err(format!("({x}, {y}) is outside the lab").into());
+        }
+        if !matches!(lab.cell(x, y), Position::Empty) || labs.iter().any(|l| l.position == (x, y)) {
+            return Err(format!("({x}, {y}) is not an empty cell").into());
+        }
+
+        for lab in &mut labs {
+            *lab.cell_mut(x, y) = Position::Obstructed;
+        }
+    }
+
+    let frame = Duration::from_secs_f64(1.0 / fps);
+    let mut escapes = vec![None; labs.len()];
+    let mut step = 0;
+
+    loop {
+        print!("\x1b[H\x1b[2J{}", render(&labs, &escapes, obstruction));
+        println!("Step {step}");
+
+        if !escapes.contains(&None) {
+            break;
+        }
+
+        thread::sleep(frame);
+
+        for (guard, lab) in labs.iter_mut().enumerate() {
+            if escapes[guard].is_some() {
+                continue;
+            }
+
+            match lab.advance() {
+                AdvanceResult::NewPosition | AdvanceResult::AlreadyVisited => {}
+                AdvanceResult::Loop => escapes[guard] = Some(false),
+                AdvanceResult::OutOfBounds => escapes[guard] = Some(true),
+            }
+        }
+
+        step += 1;
+    }
+
+    for (guard, escaped) in escapes.iter().enumerate() {
+        if *escaped == Some(false) {
+            println!("Guard {} is stuck in a loop", guard + 1);
+        }
+    }
+
+    Ok(())
+}
+
+/// Draw the lab with every cell any guard has visited marked by the
+/// direction(s) it was walked in, and each guard still inside it.
+fn render(labs: &[Lab], escapes: &[Option<bool>], obstruction: Option<(usize, usize)>) -> String {
+    let mut output = String::new();
+
+    for y in 0..labs[0].cells.height() {
+        for x in 0..labs[0].cells.width() {
+            let guard = labs
+                .iter()
+                .zip(escapes)
+                .find(|(lab, escaped)| **escaped != Some(true) && lab.position == (x, y));
+
+            let glyph = if let Some((lab, _)) = guard {
+                lab.direction.glyph()
+            } else if obstruction == Some((x, y)) {
+                'O'
+            } else if matches!(labs[0].cell(x, y), Position::Obstructed) {
+                '#'
+            } else {
+                let mut directions = Directions(0);
+                for lab in labs {
+                    if let Position::Visited(d) = lab.cell(x, y) {
+                        directions.0 |= d.0;
+                    }
+                }
+                trail(directions)
+            };
+
+            output.push(glyph);
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+fn trail(directions: Directions) -> char {
+    let vertical = directions.contains(Direction::Up) || directions.contains(Direction::Down);
+    let horizontal = directions.contains(Direction::Left) || directions.contains(Direction::Right);
+
+    match directions.0 {
+        0 => '.',
+        _ if vertical && horizontal => '+',
+        _ if directions.contains(Direction::Up) && directions.contains(Direction::Down) => '↕',
+        _ if directions.contains(Direction::Left) && directions.contains(Direction::Right) => '↔',
+        _ if directions.contains(Direction::Up) => '↑',
+        _ if directions.contains(Direction::Right) => '→',
+        _ if directions.contains(Direction::Down) => '↓',
+        _ => '←',
+    }
+}
@@ -0,0 +1,378 @@
+//! A minimal arbitrary-precision unsigned integer, for equations whose values
+//! don't fit in 64 bits.
+
+use std::cmp::Ordering;
+
+use crate::number::Number;
+
+/// Little-endian base 2³² digits, without trailing zeroes (so zero is empty).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    fn normalized(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self { limbs }
+    }
+
+    fn bits(&self) -> u64 {
+        self.limbs.last().map_or(0, |top| {
+            (self.limbs.len() as u64 - 1) * 32 + u64::from(32 - top.leading_zeros())
+        })
+    }
+
+    fn bit(&self, i: u64) -> bool {
+        self.limbs
+            .get((i / 32) as usize)
+            .is_some_and(|limb| limb >> (i % 32) & 1 == 1)
+    }
+
+    /// Multiply by `factor` and add `addend`, both small.
+    fn mul_add_small(&self, factor: u32, addend: u32) -> Self {
+        let mut carry = u64::from(addend);
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+
+        for limb in &self.limbs {
+            let n = u64::from(*limb) * u64::from(factor) + carry;
+            limbs.push(n as u32);
+            carry = n >> 32;
+        }
+        limbs.push(carry as u32);
+
+        Self::normalized(limbs)
+    }
+
+    /// Divide by a small nonzero `divisor`, returning the quotient and
+    /// remainder.
+    fn div_rem_small(&self, divisor: u32) -> (Self, u32) {
+        let mut remainder = 0u64;
+        let mut limbs = vec![0; self.limbs.len()];
+
+        for (i, limb) in self.limbs.iter().enumerate().rev() {
+            let n = remainder << 32 | u64::from(*limb);
+            limbs[i] = (n / u64::from(divisor)) as u32;
+            remainder = n % u64::from(divisor);
+        }
+
+        (Self::normalized(limbs), remainder as u32)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const CHUNK: u32 = 1_000_000_000;
+
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, chunk) = rest.div_rem_small(CHUNK);
+            chunks.push(chunk);
+            rest = quotient;
+        }
+
+        let Some((most_significant, rest)) = chunks.split_last() else {
+            return f.write_str("0");
+        };
+
+        write!(f, "{most_significant}")?;
+        for chunk in rest.iter().rev() {
+            write!(f, "{chunk:09}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseBigUintError;
+
+impl std::fmt::Display for ParseBigUintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid digit found in string")
+    }
+}
+
+impl std::error::Error for ParseBigUintError {}
+
+impl std::str::FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseBigUintError);
+        }
+
+        s.chars().try_fold(Self::from_u64(0), |n, c| {
+            let digit = c.to_digit(10).ok_or(ParseBigUintError)?;
+            Ok(n.mul_add_small(10, digit))
+        })
+    }
+}
+
+impl Number for BigUint {
+    fn from_u64(n: u64) -> Self {
+        Self::normalized(vec![n as u32, (n >> 32) as u32])
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        let mut carry = 0u64;
+        let mut limbs = Vec::with_capacity(self.limbs.len().max(rhs.limbs.len()) + 1);
+
+        for i in 0..self.limbs.len().max(rhs.limbs.len()) {
+            let a = self.limbs.get(i).copied().unwrap_or(0);
+            let b = rhs.limbs.get(i).copied().unwrap_or(0);
+            let n = u64::from(a) + u64::from(b) + carry;
+            limbs.push(n as u32);
+            carry = n >> 32;
+        }
+        limbs.push(carry as u32);
+
+        Some(Self::normalized(limbs))
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        if self < rhs {
+            return None;
+        }
+
+        let mut borrow = 0i64;
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+
+        for (i, a) in self.limbs.iter().enumerate() {
+            let b = rhs.limbs.get(i).copied().unwrap_or(0);
+            let mut n = i64::from(*a) - i64::from(b) - borrow;
+            borrow = 0;
+            if n < 0 {
+                n += 1 << 32;
+                borrow = 1;
+            }
+            limbs.push(n as u32);
+        }
+
+        Some(Self::normalized(limbs))
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        let mut limbs = vec![0u32; self.limbs.len() + rhs.limbs.len()];
+
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in rhs.limbs.iter().enumerate() {
+                let n = u64::from(*a) * u64::from(*b) + u64::from(limbs[i + j]) + carry;
+                limbs[i + j] = n as u32;
+                carry = n >> 32;
+            }
+            limbs[i + rhs.limbs.len()] = carry as u32;
+        }
+
+        Some(Self::normalized(limbs))
+    }
+
+    fn div_rem(&self, rhs: &Self) -> Option<(Self, Self)> {
+        if rhs.is_zero() {
+            return None;
+        }
+
+        // schoolbook long division, one bit at a time
+        let mut quotient = vec![0u32; self.limbs.len()];
+        let mut remainder = Self::from_u64(0);
+
+        for i in (0..self.bits()).rev() {
+            remainder = remainder.mul_add_small(2, u32::from(self.bit(i)));
+            if remainder >= *rhs {
+                remainder = remainder.checked_sub(rhs)?;
+                quotient[(i / 32) as usize] |= 1 << (i % 32);
+            }
+        }
+
+        Some((Self::normalized(quotient), remainder))
+    }
+
+    fn xor(&self, rhs: &Self) -> Self {
+        let limbs = (0..self.limbs.len().max(rhs.limbs.len()))
+            .map(|i| {
+                self.limbs.get(i).copied().unwrap_or(0) ^ rhs.limbs.get(i).copied().unwrap_or(0)
+            })
+            .collect();
+        Self::normalized(limbs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{operator, Equation, Evaluation};
+
+    fn big(n: u128) -> BigUint {
+        BigUint::normalized((0..4).map(|i| (n >> (32 * i)) as u32).collect())
+    }
+
+    fn value(n: &BigUint) -> u128 {
+        assert!(n.limbs.len() <= 4, "{n:?} doesn't fit in a u128");
+        assert_ne!(n.limbs.last(), Some(&0), "{n:?} isn't normalized");
+        n.limbs
+            .iter()
+            .rev()
+            .fold(0, |value, limb| value << 32 | u128::from(*limb))
+    }
+
+    /// Zero, values either side of each limb boundary, and a spread of
+    /// pseudorandom values of every width.
+    fn samples() -> Vec<u128> {
+        let mut samples = vec![
+            0,
+            1,
+            2,
+            10,
+            u128::from(u32::MAX),
+            1 << 32,
+            (1 << 32) + 1,
+            u128::from(u64::MAX),
+            1 << 64,
+            (1 << 96) - 1,
+            1 << 96,
+            u128::MAX >> 1,
+        ];
+
+        // xorshift, which is plenty for spreading values out
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..200 {
+            let n = u128::from(next()) << 64 | u128::from(next());
+            samples.push(n >> (next() % 128));
+        }
+
+        samples
+    }
+
+    /// What `digit_shift` should give, or `None` if that doesn't fit in a
+    /// u128.
+    fn digit_shift(n: u128, base: u64) -> Option<u128> {
+        let base = u128::from(base);
+        let mut shift = base;
+        while shift <= n {
+            shift = shift.checked_mul(base)?;
+        }
+        Some(shift)
+    }
+
+    #[test]
+    fn arithmetic_matches_u128() {
+        let samples = samples();
+
+        for &a in &samples {
+            for &b in &samples {
+                let (x, y) = (big(a), big(b));
+
+                if let Some(sum) = a.checked_add(b) {
+                    assert_eq!(value(&x.checked_add(&y).unwrap()), sum, "{a} + {b}");
+                }
+                assert_eq!(
+                    x.checked_sub(&y).map(|n| value(&n)),
+                    a.checked_sub(b),
+                    "{a} - {b}"
+                );
+                if let Some(product) = a.checked_mul(b) {
+                    assert_eq!(value(&x.checked_mul(&y).unwrap()), product, "{a} * {b}");
+                }
+                assert_eq!(
+                    x.div_rem(&y).map(|(q, r)| (value(&q), value(&r))),
+                    a.checked_div(b).map(|q| (q, a % b)),
+                    "{a} / {b}"
+                );
+                assert_eq!(value(&x.xor(&y)), a ^ b, "{a} ^ {b}");
+                assert_eq!(x.cmp(&y), a.cmp(&b), "{a} cmp {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn products_beyond_u128() {
+        let n = big(u128::MAX);
+        let square = n.checked_mul(&n).unwrap();
+
+        // (2¹²⁸ - 1)² = 2²⁵⁶ - 2¹²⁹ + 1
+        assert_eq!(
+            square.limbs,
+            [1, 0, 0, 0, u32::MAX - 1, u32::MAX, u32::MAX, u32::MAX]
+        );
+        assert_eq!(square.div_rem(&n), Some((n.clone(), big(0))));
+        assert_eq!(square.checked_sub(&square), Some(big(0)));
+    }
+
+    #[test]
+    fn display_and_parse_match_u128() {
+        for n in samples() {
+            assert_eq!(big(n).to_string(), n.to_string());
+            assert_eq!(n.to_string().parse::<BigUint>().unwrap(), big(n));
+        }
+
+        assert_eq!(
+            "340282366920938463463374607431768211456"
+                .parse::<BigUint>()
+                .unwrap()
+                .to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert!("".parse::<BigUint>().is_err());
+        assert!("12a".parse::<BigUint>().is_err());
+    }
+
+    #[test]
+    fn digit_shift_matches_u128() {
+        for n in samples() {
+            for base in [2, 10, 16] {
+                if let Some(shift) = digit_shift(n, base) {
+                    assert_eq!(
+                        big(n).digit_shift(base).map(|n| value(&n)),
+                        Some(shift),
+                        "{n} in base {base}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn solves_equations_too_large_for_u64() {
+        let operators =
+            operator::parse_set::<BigUint>("+,*,||", operator::CONCATENATE_PRECEDENCE).unwrap();
+        let eqn = "184467440737095516160: 18446744073709551615 2 5 10"
+            .parse::<Equation<BigUint>>()
+            .unwrap();
+
+        let solution = eqn.solve(&operators, Evaluation::LeftToRight).unwrap();
+        assert_eq!(
+            eqn.with_operators(&solution).to_string(),
+            "184467440737095516160 = 18446744073709551615 * 2 * 5 + 10"
+        );
+    }
+}
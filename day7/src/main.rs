@@ -1,211 +1,686 @@
+use std::ops::ControlFlow;
+
+use big::BigUint;
+use number::Number;
+use operator::{Inverse, Operator, OperatorSet};
+
+mod big;
+mod number;
+mod operator;
+
+/// The operators picked for each gap between operands so far.
+type Chosen<'a, N> = [&'a dyn Operator<N>];
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let options = Options::from_args()?;
+
+    if options.big {
+        solve::<BigUint>(&options)
+    } else {
+        solve::<u64>(&options)
+    }
+}
+
+fn solve<N: Number>(options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let parse_set = |s| operator::parse_set(s, options.concat_precedence);
+    let parts = match &options.operators {
+        None => vec![
+            ("Part 1", parse_set("+,*")?),
+            ("Part 2", parse_set("+,*,||")?),
+        ],
+        Some(operators) => vec![("Total", parse_set(operators)?)],
+    };
+
     let mut equations = Vec::new();
 
     for line in std::io::stdin().lines() {
         let line = line?;
-        equations.push(line.parse::<Equation>()?);
+        equations.push(line.parse::<Equation<N>>()?);
     }
 
-    let mut part_1 = 0;
-    'equations: for eqn in &equations {
-        for operators in Operator::permute_part_1(eqn.operands.len() as u32 - 1) {
-            if eqn.is_valid(operators) {
-                part_1 += eqn.value;
-                continue 'equations;
+    for (name, operators) in &parts {
+        let total = equations
+            .iter()
+            .filter(|eqn| {
+                if options.brute_force {
+                    eqn.is_valid_by_enumeration(operators, options.evaluation)
+                } else {
+                    eqn.is_solvable(operators, options.evaluation)
+                }
+            })
+            .try_fold(N::from_u64(0), |total, eqn| total.checked_add(&eqn.value))
+            .ok_or("the total is too large, try --big")?;
+
+        println!("{name}: {total}");
+        show_solutions(&equations, operators, options.evaluation, options.show);
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+enum Show {
+    First,
+    All,
+}
+
+/// How the operators in an equation are grouped.
+#[derive(Clone, Copy)]
+enum Evaluation {
+    /// Strictly left to right, as in the puzzle.
+    LeftToRight,
+    /// Higher precedence operators first, as in conventional notation, and
+    /// then left to right.
+    Precedence,
+}
+
+struct Options {
+    big: bool,
+    brute_force: bool,
+    show: Option<Show>,
+    operators: Option<String>,
+    evaluation: Evaluation,
+    concat_precedence: u8,
+}
+
+impl Options {
+    fn from_args() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut options = Self {
+            big: false,
+            brute_force: false,
+            show: None,
+            operators: None,
+            evaluation: Evaluation::LeftToRight,
+            concat_precedence: operator::CONCATENATE_PRECEDENCE,
+        };
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--big" => options.big = true,
+                "--brute-force" => options.brute_force = true,
+                "--show" => {
+                    options.show = Some(match args.next().as_deref() {
+                        Some("first") => Show::First,
+                        Some("all") => Show::All,
+                        _ => return Err("--show expects first or all".into()),
+                    });
+                }
+                "--precedence" => options.evaluation = Evaluation::Precedence,
+                "--concat-precedence" => {
+                    let level = args.next().ok_or("--concat-precedence expects a level")?;
+                    options.concat_precedence = level.parse()?;
+                }
+                "--operators" => {
+                    let operators = args.next().ok_or("--operators expects a list")?;
+                    options.operators = Some(operators);
+                }
+                other => return Err(format!("unrecognized argument: {other}").into()),
             }
         }
-    }
 
-    println!("Part 1: {part_1}");
+        Ok(options)
+    }
+}
 
-    let mut part_2 = 0;
-    'equations: for eqn in &equations {
-        for operators in Operator::permute_part_2(eqn.operands.len() as u32 - 1) {
-            if eqn.is_valid(operators) {
-                part_2 += eqn.value;
-                continue 'equations;
+fn show_solutions<N: Number>(
+    equations: &[Equation<N>],
+    operators: &OperatorSet<N>,
+    evaluation: Evaluation,
+    show: Option<Show>,
+) {
+    for eqn in equations {
+        match show {
+            None => {}
+            Some(Show::First) => {
+                if let Some(solution) = eqn.solve(operators, evaluation) {
+                    println!("  {}", eqn.with_operators(&solution));
+                }
+            }
+            Some(Show::All) => {
+                let solutions = eqn.solutions(operators, evaluation);
+                if solutions.is_empty() {
+                    continue;
+                }
+
+                let plural = if solutions.len() == 1 { "" } else { "s" };
+                println!("  {}: {} solution{plural}", eqn.value, solutions.len());
+                for solution in &solutions {
+                    println!("    {}", eqn.with_operators(solution));
+                }
             }
         }
     }
+}
 
-    println!("Part 2: {part_2}");
+#[derive(Debug)]
+struct Equation<N> {
+    value: N,
+    operands: Box<[N]>,
+}
 
-    Ok(())
+impl<N: Number> std::str::FromStr for Equation<N> {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, rest) = s.split_once(": ").ok_or("no colon character")?;
+
+        Ok(Self {
+            value: parse_number(value)?,
+            operands: rest
+                .split(' ')
+                .map(parse_number)
+                .collect::<Result<_, _>>()?,
+        })
+    }
 }
 
-#[derive(Clone, Debug, PartialEq)]
-enum Operator {
-    Add,
-    Multiply,
-    Concatenate,
+/// Parse a number, suggesting `--big` when it's only digits but still won't
+/// fit.
+fn parse_number<N: Number>(s: &str) -> Result<N, Box<dyn std::error::Error>> {
+    s.parse().map_err(|e: N::Err| {
+        if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+            format!("{s} is too large, try --big").into()
+        } else {
+            e.into()
+        }
+    })
 }
 
-impl Operator {
-    fn permute_part_1(length: u32) -> OperatorPart1Permutation {
-        OperatorPart1Permutation {
-            length,
-            variation: 0,
+impl<N: Number> Equation<N> {
+    /// Whether some choice of `operators` makes the equation true, checked by
+    /// trying every combination in turn.
+    fn is_valid_by_enumeration(&self, operators: &OperatorSet<N>, evaluation: Evaluation) -> bool {
+        let mut found = |value: &N, _: &Chosen<'_, N>| {
+            if *value == self.value {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        };
+
+        match evaluation {
+            Evaluation::LeftToRight => enumerate(&self.operands, operators, &mut found),
+            Evaluation::Precedence => {
+                enumerate_by_precedence(&self.operands, operators, None, &mut found)
+            }
         }
+        .is_break()
+    }
+
+    /// Whether some choice of `operators` makes the equation true.
+    fn is_solvable(&self, operators: &OperatorSet<N>, evaluation: Evaluation) -> bool {
+        self.solve(operators, evaluation).is_some()
     }
 
-    fn permute_part_2(length: u32) -> OperatorPart2Permutation {
-        OperatorPart2Permutation {
-            length,
-            variation: 0,
+    /// The first choice of `operators` found that makes the equation true.
+    fn solve<'a>(
+        &self,
+        operators: &'a OperatorSet<N>,
+        evaluation: Evaluation,
+    ) -> Option<Vec<&'a dyn Operator<N>>> {
+        let mut first = None;
+        let _ = self.find(operators, evaluation, &mut |solution| {
+            first = Some(solution);
+            ControlFlow::Break(())
+        });
+        first
+    }
+
+    /// Every choice of `operators` that makes the equation true.
+    fn solutions<'a>(
+        &self,
+        operators: &'a OperatorSet<N>,
+        evaluation: Evaluation,
+    ) -> Vec<Vec<&'a dyn Operator<N>>> {
+        let mut solutions = Vec::new();
+        let _ = self.find(operators, evaluation, &mut |solution| {
+            solutions.push(solution);
+            ControlFlow::Continue(())
+        });
+        solutions
+    }
+
+    /// Pass each choice of `operators` that makes the equation true to
+    /// `found`.
+    fn find<'a>(
+        &self,
+        operators: &'a OperatorSet<N>,
+        evaluation: Evaluation,
+        found: &mut dyn FnMut(Vec<&'a dyn Operator<N>>) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        match evaluation {
+            Evaluation::LeftToRight => search(
+                self.value.clone(),
+                &self.operands,
+                operators,
+                &mut Vec::new(),
+                found,
+            ),
+            Evaluation::Precedence => search_by_precedence(
+                self.value.clone(),
+                &self.operands,
+                operators,
+                None,
+                &mut Vec::new(),
+                found,
+            ),
+        }
+    }
+
+    /// Display the equation with `operators` filled in, e.g.
+    /// `3267 = 81 + 40 * 27`.
+    fn with_operators<'a>(&'a self, operators: &'a Chosen<'a, N>) -> WithOperators<'a, N> {
+        WithOperators {
+            equation: self,
+            operators,
         }
     }
 }
 
-struct OperatorPart1Permutation {
-    length: u32,
-    variation: u64,
+struct WithOperators<'a, N> {
+    equation: &'a Equation<N>,
+    operators: &'a Chosen<'a, N>,
 }
 
-impl Iterator for OperatorPart1Permutation {
-    type Item = OperatorPart1Iter;
+impl<N: Number> std::fmt::Display for WithOperators<'_, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut operands = self.equation.operands.iter();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if (self.variation >> self.length) != 0 {
-            return None;
+        write!(f, "{} =", self.equation.value)?;
+        if let Some(first) = operands.next() {
+            write!(f, " {first}")?;
+        }
+        for (operand, operator) in operands.zip(self.operators) {
+            write!(f, " {operator} {operand}")?;
         }
 
-        let data = self.variation;
-        self.variation += 1;
-
-        Some(OperatorPart1Iter {
-            length: self.length,
-            data,
-            position: 0,
-        })
+        Ok(())
     }
 }
 
-#[derive(Debug)]
-struct OperatorPart1Iter {
-    length: u32,
-    data: u64,
-    position: u32,
-}
-
-impl Iterator for OperatorPart1Iter {
-    type Item = Operator;
+/// Search for ways to reach `target` by working backwards from the last
+/// operand, undoing each operator in turn, and pass each one to `found`. Since
+/// evaluation is left to right, the last operator applied is always the
+/// rightmost one, and most choices can be ruled out immediately: subtraction
+/// must not underflow, division must be exact, and concatenation must leave
+/// the operand's digits at the end.
+///
+/// Where an operator can't be undone, the remaining operands are evaluated
+/// forwards instead.
+fn search<'a, N: Number>(
+    target: N,
+    operands: &[N],
+    operators: &'a OperatorSet<N>,
+    chosen: &mut Vec<&'a dyn Operator<N>>,
+    found: &mut dyn FnMut(Vec<&'a dyn Operator<N>>) -> ControlFlow<()>,
+) -> ControlFlow<()> {
+    let Some((last, rest)) = operands.split_last() else {
+        return ControlFlow::Continue(());
+    };
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.position >= self.length {
-            return None;
+    if rest.is_empty() {
+        if target == *last {
+            // operators were chosen from right to left
+            return found(chosen.iter().rev().copied().collect());
         }
+        return ControlFlow::Continue(());
+    }
 
-        let msk = 1 << self.position;
+    for operator in operators {
+        let operator = operator.as_ref();
+        chosen.push(operator);
 
-        let op = if self.data & msk == msk {
-            Operator::Multiply
-        } else {
-            Operator::Add
+        let mut found_forwards = |value: &N, prefix: &Chosen<'a, N>| {
+            if operator.apply(value, last).as_ref() != Some(&target) {
+                return ControlFlow::Continue(());
+            }
+            found(prefix.iter().chain(chosen.iter().rev()).copied().collect())
         };
 
-        self.position += 1;
+        let flow = match operator.invert(&target, last) {
+            Inverse::None => ControlFlow::Continue(()),
+            Inverse::One(target) => search(target, rest, operators, chosen, found),
+            Inverse::Any | Inverse::Unknown => enumerate(rest, operators, &mut found_forwards),
+        };
 
-        Some(op)
+        chosen.pop();
+        flow?;
     }
-}
 
-#[derive(Debug)]
-struct OperatorPart2Permutation {
-    length: u32,
-    variation: u64,
+    ControlFlow::Continue(())
 }
 
-impl Iterator for OperatorPart2Permutation {
-    type Item = OperatorPart2Iter;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.variation >= 3u64.pow(self.length) {
-            return None;
+/// Like [`search`], but grouping operators by precedence, and only choosing
+/// operators with precedence above `above`, if given.
+///
+/// The last operator applied is the rightmost of those with the lowest
+/// precedence, so each gap is tried as that operator in turn. The operands to
+/// its right form a single term of tighter binding operators, which is
+/// evaluated forwards, and the operands to its left are searched backwards
+/// from whatever the term leaves, with operators binding at least as tightly.
+fn search_by_precedence<'a, N: Number>(
+    target: N,
+    operands: &[N],
+    operators: &'a OperatorSet<N>,
+    above: Option<u8>,
+    chosen: &mut Vec<&'a dyn Operator<N>>,
+    found: &mut dyn FnMut(Vec<&'a dyn Operator<N>>) -> ControlFlow<()>,
+) -> ControlFlow<()> {
+    if let [only] = operands {
+        if target == *only {
+            // operators were chosen from right to left
+            return found(chosen.iter().rev().copied().collect());
         }
+        return ControlFlow::Continue(());
+    }
 
-        let data = self.variation;
-        self.variation += 1;
+    for split in (1..operands.len()).rev() {
+        let (lhs, term) = operands.split_at(split);
 
-        Some(OperatorPart2Iter {
-            step: self.length,
-            data,
-        })
+        for operator in operators {
+            let operator = operator.as_ref();
+            let precedence = operator.precedence();
+            if above.is_some_and(|above| precedence <= above) {
+                continue;
+            }
+
+            enumerate_by_precedence(term, operators, Some(precedence), &mut |rhs, term_ops| {
+                let mut found_forwards = |value: &N, prefix: &Chosen<'a, N>| {
+                    if operator.apply(value, rhs).as_ref() != Some(&target) {
+                        return ControlFlow::Continue(());
+                    }
+                    found(
+                        prefix
+                            .iter()
+                            .chain([&operator])
+                            .chain(term_ops)
+                            .chain(chosen.iter().rev())
+                            .copied()
+                            .collect(),
+                    )
+                };
+
+                match operator.invert(&target, rhs) {
+                    Inverse::None => ControlFlow::Continue(()),
+                    Inverse::One(target) => {
+                        let len = chosen.len();
+                        chosen.extend(term_ops.iter().rev());
+                        chosen.push(operator);
+                        let flow = search_by_precedence(
+                            target,
+                            lhs,
+                            operators,
+                            precedence.checked_sub(1),
+                            chosen,
+                            found,
+                        );
+                        chosen.truncate(len);
+                        flow
+                    }
+                    Inverse::Any | Inverse::Unknown => enumerate_by_precedence(
+                        lhs,
+                        operators,
+                        precedence.checked_sub(1),
+                        &mut found_forwards,
+                    ),
+                }
+            })?;
+        }
     }
-}
 
-#[derive(Debug)]
-struct OperatorPart2Iter {
-    step: u32,
-    data: u64,
+    ControlFlow::Continue(())
 }
 
-impl Iterator for OperatorPart2Iter {
-    type Item = Operator;
+/// Evaluate `operands` left to right with every combination of `operators`,
+/// passing each result and the operators that produced it to `found`.
+fn enumerate<'a, N: Number>(
+    operands: &[N],
+    operators: &'a OperatorSet<N>,
+    found: &mut dyn FnMut(&N, &Chosen<'a, N>) -> ControlFlow<()>,
+) -> ControlFlow<()> {
+    fn walk<'a, N: Number>(
+        value: N,
+        operands: &[N],
+        operators: &'a OperatorSet<N>,
+        chosen: &mut Vec<&'a dyn Operator<N>>,
+        found: &mut dyn FnMut(&N, &Chosen<'a, N>) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        let Some((next, rest)) = operands.split_first() else {
+            return found(&value, chosen);
+        };
+
+        for operator in operators {
+            let Some(value) = operator.apply(&value, next) else {
+                continue;
+            };
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.step == 0 {
-            return None;
+            chosen.push(operator.as_ref());
+            let flow = walk(value, rest, operators, chosen, found);
+            chosen.pop();
+            flow?;
         }
 
-        self.step -= 1;
+        ControlFlow::Continue(())
+    }
+
+    let Some((first, rest)) = operands.split_first() else {
+        return ControlFlow::Continue(());
+    };
 
-        let op = match self.data % 3 {
-            0 => Operator::Add,
-            1 => Operator::Multiply,
-            2 => Operator::Concatenate,
-            n => unreachable!("self.data % 3 yielded {n}"),
+    walk(first.clone(), rest, operators, &mut Vec::new(), found)
+}
+
+/// Like [`enumerate`], but grouping operators by precedence: each operand is
+/// combined with higher precedence operators on either side of it first, and
+/// operators of the same precedence are applied left to right. Only operators
+/// with precedence above `above` are used, if it's given.
+fn enumerate_by_precedence<'a, N: Number>(
+    operands: &[N],
+    operators: &'a OperatorSet<N>,
+    above: Option<u8>,
+    found: &mut dyn FnMut(&N, &Chosen<'a, N>) -> ControlFlow<()>,
+) -> ControlFlow<()> {
+    /// `pending` holds the left-hand side of every operator still waiting for
+    /// its right-hand side to be complete, in increasing order of precedence,
+    /// and `current` is the most recent operand with any higher precedence
+    /// operators already applied.
+    fn walk<'a, N: Number>(
+        pending: &[(N, &'a dyn Operator<N>)],
+        current: N,
+        operands: &[N],
+        operators: &'a OperatorSet<N>,
+        above: Option<u8>,
+        chosen: &mut Vec<&'a dyn Operator<N>>,
+        found: &mut dyn FnMut(&N, &Chosen<'a, N>) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        let Some((next, rest)) = operands.split_first() else {
+            let Some(value) = reduce(pending, current, 0) else {
+                return ControlFlow::Continue(());
+            };
+            return found(&value, chosen);
         };
 
-        self.data /= 3;
+        for operator in operators {
+            let operator = operator.as_ref();
+            if above.is_some_and(|above| operator.precedence() <= above) {
+                continue;
+            }
 
-        Some(op)
+            // everything pending that binds at least as tightly as this
+            // operator has its right-hand side now
+            let keep = pending
+                .iter()
+                .position(|(_, op)| op.precedence() >= operator.precedence())
+                .unwrap_or(pending.len());
+            let Some(lhs) = reduce(&pending[keep..], current.clone(), 0) else {
+                continue;
+            };
+
+            let mut pending = pending[..keep].to_vec();
+            pending.push((lhs, operator));
+
+            chosen.push(operator);
+            let flow = walk(
+                &pending,
+                next.clone(),
+                rest,
+                operators,
+                above,
+                chosen,
+                found,
+            );
+            chosen.pop();
+            flow?;
+        }
+
+        ControlFlow::Continue(())
     }
-}
 
-#[derive(Debug)]
-struct Equation {
-    value: u64,
-    operands: Box<[u64]>,
+    /// Apply every pending operator with at least `precedence`, innermost
+    /// first.
+    fn reduce<N: Number>(
+        pending: &[(N, &dyn Operator<N>)],
+        mut current: N,
+        precedence: u8,
+    ) -> Option<N> {
+        for (lhs, operator) in pending.iter().rev() {
+            if operator.precedence() < precedence {
+                break;
+            }
+            current = operator.apply(lhs, &current)?;
+        }
+        Some(current)
+    }
+
+    let Some((first, rest)) = operands.split_first() else {
+        return ControlFlow::Continue(());
+    };
+
+    walk(
+        &[],
+        first.clone(),
+        rest,
+        operators,
+        above,
+        &mut Vec::new(),
+        found,
+    )
 }
 
-impl std::str::FromStr for Equation {
-    type Err = Box<dyn std::error::Error>;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (value, rest) = s.split_once(": ").ok_or("no colon character")?;
-        let value = value.parse()?;
+    fn equation(line: &str) -> Equation<u64> {
+        line.parse().expect("an equation")
+    }
 
-        Ok(Self {
-            value,
-            operands: rest.split(' ').map(str::parse).collect::<Result<_, _>>()?,
-        })
+    fn operators(set: &str) -> Vec<Box<dyn Operator<u64>>> {
+        operator::parse_set(set, operator::CONCATENATE_PRECEDENCE).expect("operators")
     }
-}
 
-impl Equation {
-    fn is_valid(&self, operators: impl IntoIterator<Item = Operator>) -> bool {
-        eval(&self.operands, operators) == self.value
+    #[test]
+    fn lists_every_solution() {
+        let eqn = equation("3267: 81 40 27");
+        let operators = operators("+,*");
+
+        let solutions = eqn
+            .solutions(&operators, Evaluation::LeftToRight)
+            .iter()
+            .map(|solution| eqn.with_operators(solution).to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(solutions, ["3267 = 81 * 40 + 27", "3267 = 81 + 40 * 27"]);
     }
-}
 
-fn eval(operands: &[u64], operators: impl IntoIterator<Item = Operator>) -> u64 {
-    let mut iter = operands.iter().copied();
-    let Some(mut output) = iter.next() else {
-        return 0;
-    };
+    #[test]
+    fn unsolvable_equation() {
+        let eqn = equation("83: 17 5");
+        assert!(eqn
+            .solve(&operators("+,*"), Evaluation::LeftToRight)
+            .is_none());
+    }
+    /// Every solution with precedence, each written out, found by evaluating
+    /// every combination of `operators`.
+    fn enumerated(eqn: &Equation<u64>, operators: &OperatorSet<u64>) -> Vec<String> {
+        let mut solutions = Vec::new();
+        let _ = enumerate_by_precedence(&eqn.operands, operators, None, &mut |value, chosen| {
+            if *value == eqn.value {
+                solutions.push(eqn.with_operators(chosen).to_string());
+            }
+            ControlFlow::Continue(())
+        });
+        solutions.sort();
+        solutions
+    }
+
+    /// Every solution with precedence, each written out, found by searching
+    /// backwards.
+    fn searched(eqn: &Equation<u64>, operators: &OperatorSet<u64>) -> Vec<String> {
+        let mut solutions = eqn
+            .solutions(operators, Evaluation::Precedence)
+            .iter()
+            .map(|solution| eqn.with_operators(solution).to_string())
+            .collect::<Vec<_>>();
+        solutions.sort();
+        solutions
+    }
 
-    for (n, o) in iter.zip(operators) {
-        match o {
-            Operator::Add => output += n,
-            Operator::Multiply => output *= n,
-            Operator::Concatenate => {
-                let log = n.ilog10();
-                let mut shift = 10u64.pow(log);
-                shift *= 10;
-                output = output * shift + n;
+    #[test]
+    fn precedence_search_matches_enumeration() {
+        // where multiplying by zero or taking the max can't be undone
+        let fixed = [
+            "0: 3 4 0",
+            "3: 3 4 0",
+            "12: 5 12",
+            "12: 12 3 12",
+            "17: 5 12 0 5",
+        ];
+
+        // xorshift, which is plenty for spreading values out
+        let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+        let mut next = |n: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as usize % n
+        };
+        let operands = (0..40)
+            .map(|_| (0..=next(5)).map(|_| next(13) as u64).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        for set in ["+,*", "+,*,||", "+,*,-,^,max", "max,||,^"] {
+            for concat_precedence in [0, 1, operator::CONCATENATE_PRECEDENCE] {
+                let operators = operator::parse_set(set, concat_precedence).unwrap();
+                let mut equations = fixed.map(equation).into_iter().collect::<Vec<_>>();
+
+                // mostly targets that some combination reaches, and one that
+                // probably none does
+                for operands in &operands {
+                    let mut reached = Vec::new();
+                    let _ = enumerate_by_precedence(operands, &operators, None, &mut |value, _| {
+                        reached.push(*value);
+                        ControlFlow::Continue(())
+                    });
+                    for value in [reached[next(reached.len())], reached[0], next(200) as u64] {
+                        equations.push(Equation {
+                            value,
+                            operands: operands.clone().into(),
+                        });
+                    }
+                }
+
+                for eqn in &equations {
+                    assert_eq!(
+                        searched(eqn, &operators),
+                        enumerated(eqn, &operators),
+                        "{set} with concatenation at {concat_precedence}: {}: {:?}",
+                        eqn.value,
+                        eqn.operands,
+                    );
+                }
             }
         }
     }
-
-    output
 }
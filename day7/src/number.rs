@@ -0,0 +1,73 @@
+//! The arithmetic needed to evaluate equations, so that they can be solved with
+//! either fixed-size or arbitrary-precision integers.
+
+use std::{fmt::Display, str::FromStr};
+
+/// An unsigned integer. Every operation that could overflow is checked, and
+/// overflow means a result can't be reached.
+pub trait Number: Clone + Ord + Display + FromStr<Err: std::error::Error + 'static> {
+    fn from_u64(n: u64) -> Self;
+
+    fn is_zero(&self) -> bool;
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+
+    /// The quotient and remainder, or `None` when dividing by zero.
+    fn div_rem(&self, rhs: &Self) -> Option<(Self, Self)>;
+
+    fn xor(&self, rhs: &Self) -> Self;
+
+    /// The smallest power of `base` greater than this number (or `base` for
+    /// zero), i.e. what the number's digits get shifted by when another
+    /// number is written in front of it.
+    fn digit_shift(&self, base: u64) -> Option<Self> {
+        let base = Self::from_u64(base);
+        let mut shift = base.clone();
+        let mut rest = self.div_rem(&base)?.0;
+
+        while !rest.is_zero() {
+            shift = shift.checked_mul(&base)?;
+            rest = rest.div_rem(&base)?.0;
+        }
+
+        Some(shift)
+    }
+}
+
+impl Number for u64 {
+    fn from_u64(n: u64) -> Self {
+        n
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == 0
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        u64::checked_add(*self, *rhs)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        u64::checked_sub(*self, *rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        u64::checked_mul(*self, *rhs)
+    }
+
+    fn div_rem(&self, rhs: &Self) -> Option<(Self, Self)> {
+        Some((self.checked_div(*rhs)?, self % rhs))
+    }
+
+    fn xor(&self, rhs: &Self) -> Self {
+        self ^ rhs
+    }
+
+    fn digit_shift(&self, base: u64) -> Option<Self> {
+        base.checked_pow(self.checked_ilog(base).unwrap_or(0) + 1)
+    }
+}
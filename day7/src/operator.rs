@@ -0,0 +1,234 @@
+//! The operators that can be placed between an equation's operands.
+
+use std::fmt::Display;
+
+use crate::number::Number;
+
+/// A binary operator, applied left to right like every other unless
+/// precedence is taken into account.
+pub trait Operator<N>: Display {
+    /// The result of `lhs` followed by this operator and `rhs`, or `None` if
+    /// that isn't defined (e.g. a negative difference) or doesn't fit in `N`.
+    fn apply(&self, lhs: &N, rhs: &N) -> Option<N>;
+
+    /// Which left-hand operands give `result` when combined with `rhs`.
+    fn invert(&self, result: &N, rhs: &N) -> Inverse<N>;
+
+    /// How tightly the operator binds when precedence is taken into account;
+    /// higher binds tighter.
+    fn precedence(&self) -> u8;
+}
+
+pub enum Inverse<N> {
+    /// No left-hand operand works.
+    None,
+    /// Exactly this left-hand operand works.
+    One(N),
+    /// Every left-hand operand works.
+    Any,
+    /// The operator can't be undone, so the candidates have to be tried.
+    Unknown,
+}
+
+pub type OperatorSet<N> = [Box<dyn Operator<N>>];
+
+/// The default precedence of concatenation, which binds tighter than any
+/// arithmetic like juxtaposed digits would.
+pub const CONCATENATE_PRECEDENCE: u8 = 3;
+
+/// Parse a comma-separated list of operators, like `+,*,||`.
+///
+/// Besides the puzzle's `+`, `*` and `||`, this understands `-`, `^` (xor),
+/// `max` and `||k` for concatenation of digits in base `k`. Concatenation is
+/// given `concat_precedence`.
+pub fn parse_set<N: Number>(
+    s: &str,
+    concat_precedence: u8,
+) -> Result<Vec<Box<dyn Operator<N>>>, String> {
+    s.split(',')
+        .map(|op| parse(op.trim(), concat_precedence))
+        .collect()
+}
+
+fn parse<N: Number>(s: &str, concat_precedence: u8) -> Result<Box<dyn Operator<N>>, String> {
+    Ok(match s {
+        "+" => Box::new(Add),
+        "*" => Box::new(Multiply),
+        "-" => Box::new(Subtract),
+        "^" => Box::new(Xor),
+        "max" => Box::new(Max),
+        "||" => Box::new(Concatenate {
+            base: 10,
+            precedence: concat_precedence,
+        }),
+        _ => {
+            let base = s
+                .strip_prefix("||")
+                .and_then(|base| base.parse().ok())
+                .filter(|base| *base >= 2)
+                .ok_or_else(|| format!("unknown operator {s:?}"))?;
+            Box::new(Concatenate {
+                base,
+                precedence: concat_precedence,
+            })
+        }
+    })
+}
+
+struct Add;
+
+impl Display for Add {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("+")
+    }
+}
+
+impl<N: Number> Operator<N> for Add {
+    fn apply(&self, lhs: &N, rhs: &N) -> Option<N> {
+        lhs.checked_add(rhs)
+    }
+
+    fn invert(&self, result: &N, rhs: &N) -> Inverse<N> {
+        result.checked_sub(rhs).map_or(Inverse::None, Inverse::One)
+    }
+
+    fn precedence(&self) -> u8 {
+        1
+    }
+}
+
+struct Multiply;
+
+impl Display for Multiply {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("*")
+    }
+}
+
+impl<N: Number> Operator<N> for Multiply {
+    fn apply(&self, lhs: &N, rhs: &N) -> Option<N> {
+        lhs.checked_mul(rhs)
+    }
+
+    fn invert(&self, result: &N, rhs: &N) -> Inverse<N> {
+        match result.div_rem(rhs) {
+            // anything times zero is zero
+            None if result.is_zero() => Inverse::Any,
+            Some((quotient, remainder)) if remainder.is_zero() => Inverse::One(quotient),
+            _ => Inverse::None,
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        2
+    }
+}
+
+/// Append the digits of the right-hand side to the left-hand side.
+struct Concatenate {
+    base: u64,
+    precedence: u8,
+}
+
+impl Display for Concatenate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.base == 10 {
+            f.write_str("||")
+        } else {
+            write!(f, "||{}", self.base)
+        }
+    }
+}
+
+impl<N: Number> Operator<N> for Concatenate {
+    fn apply(&self, lhs: &N, rhs: &N) -> Option<N> {
+        lhs.checked_mul(&rhs.digit_shift(self.base)?)?
+            .checked_add(rhs)
+    }
+
+    fn invert(&self, result: &N, rhs: &N) -> Inverse<N> {
+        // a shift that doesn't fit means `rhs` has more digits than `result`
+        let Some(shift) = rhs.digit_shift(self.base) else {
+            return Inverse::None;
+        };
+
+        match result.div_rem(&shift) {
+            Some((quotient, remainder)) if remainder == *rhs => Inverse::One(quotient),
+            _ => Inverse::None,
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        self.precedence
+    }
+}
+
+struct Subtract;
+
+impl Display for Subtract {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("-")
+    }
+}
+
+impl<N: Number> Operator<N> for Subtract {
+    fn apply(&self, lhs: &N, rhs: &N) -> Option<N> {
+        lhs.checked_sub(rhs)
+    }
+
+    fn invert(&self, result: &N, rhs: &N) -> Inverse<N> {
+        result.checked_add(rhs).map_or(Inverse::None, Inverse::One)
+    }
+
+    fn precedence(&self) -> u8 {
+        1
+    }
+}
+
+struct Xor;
+
+impl Display for Xor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("^")
+    }
+}
+
+impl<N: Number> Operator<N> for Xor {
+    fn apply(&self, lhs: &N, rhs: &N) -> Option<N> {
+        Some(lhs.xor(rhs))
+    }
+
+    fn invert(&self, result: &N, rhs: &N) -> Inverse<N> {
+        Inverse::One(result.xor(rhs))
+    }
+
+    fn precedence(&self) -> u8 {
+        0
+    }
+}
+
+struct Max;
+
+impl Display for Max {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("max")
+    }
+}
+
+impl<N: Number> Operator<N> for Max {
+    fn apply(&self, lhs: &N, rhs: &N) -> Option<N> {
+        Some(lhs.max(rhs).clone())
+    }
+
+    fn invert(&self, result: &N, rhs: &N) -> Inverse<N> {
+        match result.cmp(rhs) {
+            std::cmp::Ordering::Less => Inverse::None,
+            std::cmp::Ordering::Equal => Inverse::Unknown,
+            std::cmp::Ordering::Greater => Inverse::One(result.clone()),
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        2
+    }
+}
@@ -1,16 +1,28 @@
-use std::{
-    collections::{HashMap, HashSet},
-    ops::{Add, Sub},
-};
+use std::collections::{HashMap, HashSet};
+
+use aoc::VecN;
+use placement::Placement;
+
+mod placement;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut map = Map::from_stdin()?;
+    let options = Options::from_args()?;
+
+    if options.layers {
+        solve(Map::from_stdin_layers(&options.alphabet)?, &options);
+    } else {
+        solve(Map::from_stdin(&options.alphabet)?, &options);
+    }
+
+    Ok(())
+}
 
+fn solve<const N: usize>(mut map: Map<N>, options: &Options) {
     let mut part_1 = HashSet::new();
     let mut part_2 = HashSet::new();
 
     for frequency in map.frequencies.values_mut() {
-        frequency.find_antinodes(&map.size);
+        frequency.find_antinodes(&map.size, &options.placement, options.harmonics);
 
         part_1.extend(frequency.part_1.iter().copied());
 
@@ -19,95 +31,353 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     println!("Part 1: {}", part_1.len());
-    println!("Part 1: {}", part_2.len());
+    println!("Part 2: {}", part_2.len());
 
-    Ok(())
+    if let Some(part) = options.render {
+        print!("{}", render(&map, part, options.frequency));
+    }
+
+    if options.table {
+        print!("{}", table(&map));
+    }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-struct Vec2<T> {
-    x: T,
-    y: T,
+#[derive(Clone, Copy)]
+enum Part {
+    One,
+    Two,
 }
 
-impl Vec2<isize> {
-    fn is_inside(&self, size: &Vec2<usize>) -> bool {
-        if self.x < 0 || self.y < 0 {
-            return false;
+/// Which points on the line through a pair of antennae count as antinodes
+/// in part 2.
+#[derive(Clone, Copy)]
+enum Harmonics {
+    /// Whole multiples of the distance between the antennae, as the puzzle
+    /// describes.
+    Stepped,
+    /// Every grid point on the line, including those between multiples when
+    /// the antennae aren't adjacent lattice points of it.
+    Resonant,
+}
+
+impl std::str::FromStr for Harmonics {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stepped" => Ok(Self::Stepped),
+            "resonant" => Ok(Self::Resonant),
+            other => Err(format!(
+                "unknown harmonics {other:?} (expected stepped or resonant)"
+            )),
         }
+    }
+}
+
+/// Which glyphs mark antennae. Empty cells are always `.`.
+enum Alphabet {
+    /// ASCII letters and digits, as in the puzzle.
+    Alphanumeric,
+    /// Anything visible.
+    Any,
+    /// Only these.
+    Chars(HashSet<char>),
+}
 
-        (self.x as usize) < size.x && (self.y as usize) < size.y
+impl Alphabet {
+    fn contains(&self, c: char) -> bool {
+        match self {
+            Self::Alphanumeric => c.is_ascii_alphanumeric(),
+            Self::Any => !c.is_whitespace() && !c.is_control(),
+            Self::Chars(chars) => chars.contains(&c),
+        }
     }
 }
 
-impl<T: Add> Add for Vec2<T> {
-    type Output = Vec2<T::Output>;
+impl std::str::FromStr for Alphabet {
+    type Err = String;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Vec2 {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "alphanumeric" => Ok(Self::Alphanumeric),
+            "any" => Ok(Self::Any),
+            _ => {
+                let chars = s.strip_prefix("chars:").ok_or_else(|| {
+                    format!("unknown alphabet {s:?} (expected alphanumeric, any or chars:...)")
+                })?;
+                if chars.contains('.') {
+                    return Err("`.` always marks an empty cell".into());
+                }
+                Ok(Self::Chars(chars.chars().collect()))
+            }
         }
     }
 }
 
-impl<T: Sub> Sub for Vec2<T> {
-    type Output = Vec2<T::Output>;
+struct Options {
+    alphabet: Alphabet,
+    placement: Placement,
+    harmonics: Harmonics,
+    render: Option<Part>,
+    frequency: Option<char>,
+    table: bool,
+    layers: bool,
+}
+
+impl Options {
+    fn from_args() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut options = Self {
+            alphabet: Alphabet::Alphanumeric,
+            placement: Placement::default(),
+            harmonics: Harmonics::Stepped,
+            render: None,
+            frequency: None,
+            table: false,
+            layers: false,
+        };
+        let mut args = std::env::args().skip(1);
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        Vec2 {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--alphabet" => {
+                    let alphabet = args.next().ok_or("--alphabet expects an alphabet")?;
+                    options.alphabet = alphabet.parse()?;
+                }
+                "--ratios" => {
+                    let ratios = args.next().ok_or("--ratios expects a list of ratios")?;
+                    options.placement.ratios = ratios
+                        .split(',')
+                        .map(|ratio| ratio.trim().parse())
+                        .collect::<Result<_, _>>()?;
+                }
+                "--metric" => {
+                    let metric = args.next().ok_or("--metric expects a metric")?;
+                    options.placement.metric = metric.parse()?;
+                }
+                "--harmonics" => {
+                    let harmonics = args.next().ok_or("--harmonics expects a mode")?;
+                    options.harmonics = harmonics.parse()?;
+                }
+                "--render" => {
+                    options.render = Some(match args.next().as_deref() {
+                        Some("1") => Part::One,
+                        Some("2") => Part::Two,
+                        _ => return Err("--render expects a part number (1 or 2)".into()),
+                    });
+                }
+                "--frequency" => {
+                    let frequency = args.next().ok_or("--frequency expects a character")?;
+                    let mut chars = frequency.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => options.frequency = Some(c),
+                        _ => return Err("--frequency expects a single character".into()),
+                    }
+                }
+                "--table" => options.table = true,
+                "--layers" => options.layers = true,
+                other => return Err(format!("unrecognized argument: {other}").into()),
+            }
         }
+
+        Ok(options)
     }
 }
 
-struct Map {
-    size: Vec2<usize>,
-    frequencies: HashMap<char, Frequency>,
+/// Draw the map like the puzzle does, with `#` marking the antinodes for
+/// `part` wherever there isn't already an antenna, optionally showing only one
+/// frequency. Maps with more than two dimensions are drawn as a series of
+/// layers separated by blank lines.
+fn render<const N: usize>(map: &Map<N>, part: Part, only: Option<char>) -> String {
+    let mut cells = vec!['.'; map.size.0.iter().product()];
+
+    let frequencies = map
+        .frequencies
+        .iter()
+        .filter(|(c, _)| only.is_none_or(|only| only == **c));
+
+    for (_, frequency) in frequencies.clone() {
+        let harmonics = match part {
+            Part::One => None,
+            Part::Two => Some(&frequency.part_2),
+        };
+
+        for antinode in frequency
+            .part_1
+            .iter()
+            .chain(harmonics.into_iter().flatten())
+        {
+            cells[antinode.index(&map.size)] = '#';
+        }
+    }
+
+    for (c, frequency) in frequencies {
+        for antenna in &frequency.antennae {
+            cells[antenna.index(&map.size)] = *c;
+        }
+    }
+
+    let width = map.size.0.first().copied().unwrap_or(1).max(1);
+    let layer = map.size.0.iter().take(2).product::<usize>().max(1);
+
+    let mut output = String::new();
+    for (i, line) in cells.chunks(width).enumerate() {
+        if i > 0 && (i * width).is_multiple_of(layer) {
+            output.push('\n');
+        }
+        output.extend(line);
+        output.push('\n');
+    }
+
+    output
 }
 
-impl Map {
-    fn from_stdin() -> std::io::Result<Self> {
-        let mut width = 0;
-        let mut height = 0;
-        let mut frequencies: HashMap<char, Frequency> = HashMap::new();
+/// How many antennae and antinodes each frequency has, in order of frequency.
+fn table<const N: usize>(map: &Map<N>) -> String {
+    let mut frequencies: Vec<_> = map.frequencies.iter().collect();
+    frequencies.sort_unstable_by_key(|(c, _)| **c);
 
-        for line in std::io::stdin().lines() {
-            let line = line?;
-            width = width.max(line.len());
+    let mut output = String::from("frequency  antennae  part 1  part 2\n");
+    for (c, frequency) in frequencies {
+        let part_2 = frequency.part_1.union(&frequency.part_2).count();
+        output += &format!(
+            "{c:<9}  {:>8}  {:>6}  {part_2:>6}\n",
+            frequency.antennae.len(),
+            frequency.part_1.len(),
+        );
+    }
 
-            for (i, c) in line.char_indices() {
-                if c.is_ascii_alphanumeric() {
-                    frequencies.entry(c).or_default().antennae.push(Vec2 {
-                        x: i as isize,
-                        y: height as isize,
-                    });
+    output
+}
+
+struct Map<const N: usize> {
+    size: VecN<usize, N>,
+    frequencies: HashMap<char, Frequency<N>>,
+}
+
+impl Map<2> {
+    fn from_stdin(alphabet: &Alphabet) -> Result<Self, Box<dyn std::error::Error>> {
+        let lines = std::io::stdin().lines().collect::<Result<Vec<_>, _>>()?;
+        let mut frequencies = HashMap::new();
+
+        let (width, height) =
+            read_layer(&lines, 0, alphabet, &mut frequencies, |x, y| VecN([x, y]))?;
+
+        Ok(Self {
+            size: VecN([width, height]),
+            frequencies,
+        })
+    }
+}
+
+impl Map<3> {
+    /// Read a map made of layers, each laid out like a two-dimensional map
+    /// of the same size and separated from the next by blank lines.
+    fn from_stdin_layers(alphabet: &Alphabet) -> Result<Self, Box<dyn std::error::Error>> {
+        let lines = std::io::stdin().lines().collect::<Result<Vec<_>, _>>()?;
+        let mut frequencies = HashMap::new();
+        let mut size = None;
+        let mut first_line = 0;
+        let mut depth = 0;
+
+        for layer in lines.split(|line| line.is_empty()) {
+            // repeated or trailing blank lines leave empty groups, which
+            // aren't layers
+            if layer.is_empty() {
+                first_line += 1;
+                continue;
+            }
+
+            let z = depth as isize;
+            let layer_size = read_layer(layer, first_line, alphabet, &mut frequencies, |x, y| {
+                VecN([x, y, z])
+            })?;
+
+            match size {
+                None => size = Some(layer_size),
+                Some((width, height)) if (width, height) != layer_size => {
+                    return Err(format!(
+                        "layer {} is {}x{}, but layer 1 is {width}x{height}",
+                        z + 1,
+                        layer_size.0,
+                        layer_size.1,
+                    )
+                    .into());
                 }
+                Some(_) => {}
             }
 
-            height += 1;
+            first_line += layer.len() + 1;
+            depth += 1;
         }
 
+        let (width, height) = size.unwrap_or((0, 0));
+
         Ok(Self {
-            size: Vec2 {
-                x: width,
-                y: height,
-            },
+            size: VecN([width, height, depth]),
             frequencies,
         })
     }
 }
 
+/// Add the antennae in one two-dimensional layer of a map, placing each with
+/// `position`, and return the layer's width and height. `first_line` is the
+/// index of the layer's first line in the input, for errors.
+fn read_layer<const N: usize>(
+    lines: &[String],
+    first_line: usize,
+    alphabet: &Alphabet,
+    frequencies: &mut HashMap<char, Frequency<N>>,
+    position: impl Fn(isize, isize) -> VecN<isize, N>,
+) -> Result<(usize, usize), String> {
+    let width = lines.first().map_or(0, |line| line.chars().count());
+
+    for (y, line) in lines.iter().enumerate() {
+        let line_number = first_line + y + 1;
+
+        let length = line.chars().count();
+        if length != width {
+            return Err(format!(
+                "line {line_number} has {length} characters, but line {} has {width}",
+                first_line + 1
+            ));
+        }
+
+        for (x, c) in line.chars().enumerate() {
+            if c == '.' {
+                continue;
+            }
+            if !alphabet.contains(c) {
+                return Err(format!(
+                    "unexpected {c:?} at line {line_number}, column {}",
+                    x + 1
+                ));
+            }
+
+            frequencies
+                .entry(c)
+                .or_default()
+                .antennae
+                .push(position(x as isize, y as isize));
+        }
+    }
+
+    Ok((width, lines.len()))
+}
+
 #[derive(Default)]
-struct Frequency {
-    antennae: Vec<Vec2<isize>>,
-    part_1: HashSet<Vec2<isize>>,
-    part_2: HashSet<Vec2<isize>>,
+struct Frequency<const N: usize> {
+    antennae: Vec<VecN<isize, N>>,
+    part_1: HashSet<VecN<isize, N>>,
+    part_2: HashSet<VecN<isize, N>>,
 }
 
-impl Frequency {
-    fn find_antinodes(&mut self, size: &Vec2<usize>) {
+impl<const N: usize> Frequency<N> {
+    fn find_antinodes(
+        &mut self,
+        size: &VecN<usize, N>,
+        placement: &Placement,
+        harmonics: Harmonics,
+    ) {
         for (first, second) in self
             .antennae
             .iter()
@@ -118,37 +388,54 @@ impl Frequency {
                     .map(move |second| (first, second))
             })
         {
-            let delta = *second - *first;
-
-            let first_antinode = *first - delta;
-            let second_antinode = *second + delta;
+            self.part_1
+                .extend(placement.antinodes(*first, *second, size));
 
-            if first_antinode.is_inside(size) {
-                self.part_1.insert(first_antinode);
-            }
-
-            if second_antinode.is_inside(size) {
-                self.part_1.insert(second_antinode);
-            }
+            let delta = *second - *first;
 
-            self.part_2.insert(*first);
-            self.part_2.insert(*second);
+            let step = match harmonics {
+                Harmonics::Stepped => delta,
+                Harmonics::Resonant => delta.primitive(),
+            };
 
-            let mut w = first_antinode;
-            while {
-                w = w - delta;
-                w.is_inside(size)
-            } {
+            // walk the whole line through the first antenna, both ways
+            let mut w = *first;
+            while w.is_inside(size) {
                 self.part_2.insert(w);
+                w = w - step;
             }
 
-            let mut w = second_antinode;
-            while {
-                w = w + delta;
-                w.is_inside(size)
-            } {
+            let mut w = *first + step;
+            while w.is_inside(size) {
                 self.part_2.insert(w);
+                w = w + step;
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part_2(harmonics: Harmonics) -> HashSet<VecN<isize, 2>> {
+        let mut frequency = Frequency {
+            antennae: vec![VecN([0, 0]), VecN([2, 4])],
+            ..Default::default()
+        };
+        frequency.find_antinodes(&VecN([6, 6]), &Placement::default(), harmonics);
+        frequency.part_2
+    }
+
+    #[test]
+    fn resonant_harmonics_fill_in_lattice_points() {
+        let stepped = part_2(Harmonics::Stepped);
+        let resonant = part_2(Harmonics::Resonant);
+
+        assert_eq!(stepped, HashSet::from([VecN([0, 0]), VecN([2, 4])]));
+        assert_eq!(
+            resonant,
+            HashSet::from([VecN([0, 0]), VecN([1, 2]), VecN([2, 4])])
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! Where a pair of antennae puts its part 1 antinodes: at points whose
+//! distances to the two antennae are in one of a set of ratios.
+
+use aoc::VecN;
+
+/// Antinodes are where one antenna is `far / near` times as far away as the
+/// other.
+#[derive(Clone, Copy)]
+pub struct Ratio {
+    far: isize,
+    near: isize,
+    /// Whether points between the antennae count.
+    inside: bool,
+    /// Whether points beyond either antenna count.
+    outside: bool,
+}
+
+impl std::str::FromStr for Ratio {
+    type Err = String;
+
+    /// Parse `p:q`, optionally followed by `/inside` or `/outside` to only
+    /// place antinodes on that side of the antennae, or `midpoint` for `1:1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (ratio, side) = s.split_once('/').unwrap_or((s, "both"));
+        let (inside, outside) = match side {
+            "both" => (true, true),
+            "inside" => (true, false),
+            "outside" => (false, true),
+            other => {
+                return Err(format!(
+                    "unknown side {other:?} (expected inside or outside)"
+                ))
+            }
+        };
+
+        let (p, q) = match ratio {
+            "midpoint" => (1, 1),
+            _ => ratio
+                .split_once(':')
+                .and_then(|(p, q)| Some((p.parse().ok()?, q.parse().ok()?)))
+                .filter(|&(p, q)| p > 0 && q > 0)
+                .ok_or_else(|| format!("invalid ratio {ratio:?} (expected p:q or midpoint)"))?,
+        };
+
+        Ok(Self {
+            far: p.max(q),
+            near: p.min(q),
+            inside,
+            outside,
+        })
+    }
+}
+
+/// How distance is measured between grid points.
+#[derive(Clone, Copy)]
+pub enum Metric {
+    /// Only points on the line through the antennae count, as in the puzzle.
+    Line,
+    /// Any grid point counts, measured in steps along the axes.
+    Manhattan,
+    /// Any grid point counts, measured in king's moves.
+    Chebyshev,
+    /// Any grid point counts, measured in a straight line.
+    Euclidean,
+}
+
+impl std::str::FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "line" => Ok(Self::Line),
+            "manhattan" => Ok(Self::Manhattan),
+            "chebyshev" => Ok(Self::Chebyshev),
+            "euclidean" => Ok(Self::Euclidean),
+            other => Err(format!(
+                "unknown metric {other:?} (expected line, manhattan, chebyshev or euclidean)"
+            )),
+        }
+    }
+}
+
+impl Metric {
+    /// The distance between two points, squared for euclidean distance so
+    /// that it stays whole.
+    fn distance<const N: usize>(self, a: VecN<isize, N>, b: VecN<isize, N>) -> usize {
+        let deltas = (a - b).0.map(isize::unsigned_abs).into_iter();
+
+        match self {
+            Self::Line | Self::Euclidean => deltas.map(|d| d * d).sum(),
+            Self::Manhattan => deltas.sum(),
+            Self::Chebyshev => deltas.max().unwrap_or(0),
+        }
+    }
+}
+
+pub struct Placement {
+    pub ratios: Vec<Ratio>,
+    pub metric: Metric,
+}
+
+impl Default for Placement {
+    /// Twice as far from one antenna as the other, beyond the pair.
+    fn default() -> Self {
+        Self {
+            ratios: vec![Ratio {
+                far: 2,
+                near: 1,
+                inside: false,
+                outside: true,
+            }],
+            metric: Metric::Line,
+        }
+    }
+}
+
+impl Placement {
+    /// Every antinode of the antennae at `a` and `b` within a map of `size`.
+    ///
+    /// Inside and outside are ignored by the metrics other than `Line`, which
+    /// consider every grid point rather than just those on the line.
+    pub fn antinodes<const N: usize>(
+        &self,
+        a: VecN<isize, N>,
+        b: VecN<isize, N>,
+        size: &VecN<usize, N>,
+    ) -> Vec<VecN<isize, N>> {
+        let mut antinodes = Vec::new();
+
+        match self.metric {
+            Metric::Line => {
+                for ratio in &self.ratios {
+                    antinodes.extend(collinear(a, b, ratio));
+                    antinodes.extend(collinear(b, a, ratio));
+                }
+                antinodes.retain(|antinode| antinode.is_inside(size));
+            }
+            _ => {
+                for point in VecN::points(size) {
+                    let to_a = self.metric.distance(point, a);
+                    let to_b = self.metric.distance(point, b);
+
+                    if self.ratios.iter().any(|ratio| {
+                        let (far, near) = match self.metric {
+                            Metric::Euclidean => (ratio.far.pow(2), ratio.near.pow(2)),
+                            _ => (ratio.far, ratio.near),
+                        };
+                        let (far, near) = (far as usize, near as usize);
+                        to_a * near == to_b * far || to_b * near == to_a * far
+                    }) {
+                        antinodes.push(point);
+                    }
+                }
+            }
+        }
+
+        antinodes
+    }
+}
+
+/// The grid points on the line through `near` and `far` that are `ratio`
+/// times as far from `far` as from `near`, closer to `near`.
+fn collinear<const N: usize>(
+    near: VecN<isize, N>,
+    far: VecN<isize, N>,
+    ratio: &Ratio,
+) -> Vec<VecN<isize, N>> {
+    let delta = near - far;
+    let mut points = Vec::new();
+
+    // measured from `far` towards `near`, in units of the distance between
+    // them
+    let mut fractions = Vec::new();
+    if ratio.inside {
+        fractions.push((ratio.far, ratio.far + ratio.near));
+    }
+    if ratio.outside && ratio.far != ratio.near {
+        fractions.push((ratio.far, ratio.far - ratio.near));
+    }
+
+    for (numerator, denominator) in fractions {
+        let scaled = delta.0.map(|c| c * numerator);
+        if scaled.iter().all(|c| c % denominator == 0) {
+            points.push(far + VecN(scaled.map(|c| c / denominator)));
+        }
+    }
+
+    points
+}